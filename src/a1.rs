@@ -0,0 +1,166 @@
+use crate::error::ReadError;
+
+/// The end row of a range of full columns such as `A:D`.
+pub const LAST_ROW: u32 = u32::MAX;
+
+/// Converts a column reference into a zero-based column index.
+///
+/// Accepts Excel column letters (`"A"`, `"AB"`, case-insensitive) or a
+/// zero-based numeric index (`"3"`).
+pub fn column_index(reference: &str) -> Result<u32, ReadError> {
+    let reference = reference.trim();
+    if let Ok(index) = reference.parse::<u32>() {
+        return Ok(index);
+    }
+    if reference.is_empty() || !reference.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("Invalid column reference '{reference}'").into());
+    }
+    let mut index: u32 = 0;
+    for c in reference.chars() {
+        let digit = c.to_ascii_uppercase() as u32 - 'A' as u32 + 1;
+        index = index
+            .checked_mul(26)
+            .and_then(|i| i.checked_add(digit))
+            .ok_or_else(|| format!("Column reference '{reference}' is out of range"))?;
+    }
+    Ok(index - 1)
+}
+
+/// Splits a cell reference such as `"B2"`, `"$B$2"` or `"'Q1 Report'!B2"`
+/// into its sheet, if given, and zero-based `(row, column)` position.
+pub fn cell_reference(reference: &str) -> Result<(Option<String>, (u32, u32)), ReadError> {
+    let (sheet, cell) = split_sheet(reference);
+    let cell = cell.trim().replace('$', "");
+    let split = cell
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(|| format!("Invalid cell reference '{reference}'"))?;
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() || letters.parse::<u32>().is_ok() {
+        return Err(format!("Invalid cell reference '{reference}'").into());
+    }
+    let column = column_index(letters)?;
+    let row = digits
+        .parse::<u32>()
+        .ok()
+        .and_then(|row| row.checked_sub(1))
+        .ok_or_else(|| format!("Invalid cell reference '{reference}'"))?;
+    Ok((sheet, (row, column)))
+}
+
+/// Splits the sheet, unquoted, off a reference such as `"'Q1 Report'!B2"`.
+fn split_sheet(reference: &str) -> (Option<String>, &str) {
+    match reference.rsplit_once('!') {
+        Some((sheet, cell)) => {
+            let sheet = sheet.trim();
+            let sheet = sheet
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .unwrap_or(sheet);
+            (Some(sheet.replace("''", "'")), cell)
+        }
+        None => (None, reference),
+    }
+}
+
+/// Splits a range reference such as `"A1:D20"` or `"'Q1 Report'!$A$1:$D$20"`
+/// into its sheet, if given, and zero-based start and end positions. A single
+/// cell is a range of one. Full columns such as `"A:D"` or `"B:B"` run from
+/// the first row to [`LAST_ROW`].
+pub fn range_reference(
+    reference: &str,
+) -> Result<(Option<String>, (u32, u32), (u32, u32)), ReadError> {
+    let (prefix, cells) = match reference.rsplit_once('!') {
+        Some((sheet, cells)) => (Some(sheet), cells),
+        None => (None, reference),
+    };
+    let with_sheet = |cell: &str| match prefix {
+        Some(sheet) => format!("{sheet}!{cell}"),
+        None => cell.to_string(),
+    };
+    let (start, end) = cells.split_once(':').unwrap_or((cells, cells));
+    let is_column = |cell: &str| {
+        let cell = cell.trim().replace('$', "");
+        !cell.is_empty() && cell.chars().all(|c| c.is_ascii_alphabetic())
+    };
+    if is_column(start) && is_column(end) {
+        let (sheet, _) = split_sheet(reference);
+        let first = column_index(&start.replace('$', ""))?;
+        let last = column_index(&end.replace('$', ""))?;
+        if first > last {
+            return Err(format!("Range '{reference}' ends before it starts").into());
+        }
+        return Ok((sheet, (0, first), (LAST_ROW, last)));
+    }
+    let (sheet, start) = cell_reference(&with_sheet(start))?;
+    let (_, end) = cell_reference(&with_sheet(end))?;
+    if start.0 > end.0 || start.1 > end.1 {
+        return Err(format!("Range '{reference}' ends before it starts").into());
+    }
+    Ok((sheet, start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_reference_splits_sheet_and_position() {
+        assert_eq!(cell_reference("B2").unwrap(), (None, (1, 1)));
+        assert_eq!(cell_reference("$B$2").unwrap(), (None, (1, 1)));
+        assert_eq!(
+            cell_reference("'Q1 Report'!B2").unwrap(),
+            (Some("Q1 Report".to_string()), (1, 1))
+        );
+        assert_eq!(
+            cell_reference("'It''s'!A1").unwrap(),
+            (Some("It's".to_string()), (0, 0))
+        );
+        assert!(cell_reference("A0").is_err());
+        assert!(cell_reference("12").is_err());
+    }
+
+    #[test]
+    fn range_reference_reads_cells_and_full_columns() {
+        assert_eq!(range_reference("A1").unwrap(), (None, (0, 0), (0, 0)));
+        assert_eq!(range_reference("A1:C3").unwrap(), (None, (0, 0), (2, 2)));
+        assert_eq!(
+            range_reference("B:B").unwrap(),
+            (None, (0, 1), (LAST_ROW, 1))
+        );
+        assert_eq!(range_reference("A1:D20").unwrap(), (None, (0, 0), (19, 3)));
+        assert_eq!(
+            range_reference("'Q1 Report'!$A$1:$D$20").unwrap(),
+            (Some("Q1 Report".to_string()), (0, 0), (19, 3))
+        );
+        assert_eq!(
+            range_reference("Summary!B2").unwrap(),
+            (Some("Summary".to_string()), (1, 1), (1, 1))
+        );
+        assert_eq!(
+            range_reference("A:D").unwrap(),
+            (None, (0, 0), (LAST_ROW, 3))
+        );
+        assert_eq!(
+            range_reference("Summary!B:B").unwrap(),
+            (Some("Summary".to_string()), (0, 1), (LAST_ROW, 1))
+        );
+    }
+
+    #[test]
+    fn range_reference_rejects_backward_ranges() {
+        assert!(range_reference("D1:A1").is_err());
+        assert!(range_reference("A20:A1").is_err());
+        assert!(range_reference("B:A").is_err());
+    }
+
+    #[test]
+    fn column_index_reads_letters_and_numbers() {
+        assert_eq!(column_index("A").unwrap(), 0);
+        assert_eq!(column_index("z").unwrap(), 25);
+        assert_eq!(column_index("AA").unwrap(), 26);
+        assert_eq!(column_index("AB").unwrap(), 27);
+        assert_eq!(column_index("3").unwrap(), 3);
+        assert!(column_index("").is_err());
+        assert!(column_index("A1").is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::error::ReadError;
+use crate::get_lazy_frames;
+use polars::prelude::{DataTypeExpr, Expr, LazyFrame, NULL, PlSmallStr, Schema, lit};
+use polars_core::prelude::DataType;
+use qa_settings::Comparable;
+
+/// Which columns both sides keep after alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    /// Only the columns present on both sides.
+    Intersection,
+    /// Every column of either side; a side missing a column gets it as typed
+    /// nulls.
+    Union,
+}
+
+/// Reads both sides and aligns them to a common column set.
+pub fn get_aligned_lazy_frames(
+    comp: &Comparable,
+    mode: AlignMode,
+) -> Result<(LazyFrame, LazyFrame), ReadError> {
+    let (left, right) = get_lazy_frames(comp)?;
+    align_lazy_frames(left, right, mode)
+}
+
+/// Projects both frames onto the same columns in the same order: left's
+/// columns first, then any right-only columns in union mode.
+pub fn align_lazy_frames(
+    mut left: LazyFrame,
+    mut right: LazyFrame,
+    mode: AlignMode,
+) -> Result<(LazyFrame, LazyFrame), ReadError> {
+    let left_schema = left.collect_schema()?;
+    let right_schema = right.collect_schema()?;
+    let columns = match mode {
+        AlignMode::Intersection => left_schema
+            .iter()
+            .filter(|(name, _)| right_schema.contains(name))
+            .map(|(name, dtype)| (name.clone(), dtype.clone()))
+            .collect::<Vec<_>>(),
+        AlignMode::Union => left_schema
+            .iter()
+            .chain(
+                right_schema
+                    .iter()
+                    .filter(|(name, _)| !left_schema.contains(name)),
+            )
+            .map(|(name, dtype)| (name.clone(), dtype.clone()))
+            .collect::<Vec<_>>(),
+    };
+    Ok((
+        project(left, &left_schema, &columns),
+        project(right, &right_schema, &columns),
+    ))
+}
+
+fn project(lf: LazyFrame, schema: &Schema, columns: &[(PlSmallStr, DataType)]) -> LazyFrame {
+    lf.select(
+        columns
+            .iter()
+            .map(|(name, dtype)| {
+                if schema.contains(name) {
+                    Expr::Column(name.clone())
+                } else {
+                    lit(NULL)
+                        .cast(DataTypeExpr::from(dtype.clone()))
+                        .alias(name.clone())
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Casts the columns both frames share to their [`widest_common_type`], so a
+/// column read as `i32` on one side and `i64` on the other compares as `i64`.
+pub fn widen_lazy_frames(
+    mut left: LazyFrame,
+    mut right: LazyFrame,
+) -> Result<(LazyFrame, LazyFrame), ReadError> {
+    let left_schema = left.collect_schema()?;
+    let right_schema = right.collect_schema()?;
+    let mut left_casts = Vec::new();
+    let mut right_casts = Vec::new();
+    for (name, left_dtype) in left_schema.iter() {
+        let Some(right_dtype) = right_schema.get(name) else {
+            continue;
+        };
+        let widest = widest_common_type(left_dtype, right_dtype)
+            .map_err(|e| format!("Column '{name}': {e}"))?;
+        if &widest != left_dtype {
+            left_casts.push(Expr::Column(name.clone()).cast(DataTypeExpr::from(widest.clone())));
+        }
+        if &widest != right_dtype {
+            right_casts.push(Expr::Column(name.clone()).cast(DataTypeExpr::from(widest)));
+        }
+    }
+    Ok((
+        left.with_columns(left_casts),
+        right.with_columns(right_casts),
+    ))
+}
+
+/// The narrowest type both `left` and `right` values can be cast to:
+///
+/// - integers of the same signedness widen to the larger width;
+/// - an unsigned and a signed integer widen to a signed integer wider than
+///   the unsigned one, `u128` has none and is an error;
+/// - an integer and a float give `f32` for integers of up to 16 bits and
+///   `f64` otherwise, two floats give the wider one. Integers beyond 2^53 in
+///   magnitude don't fit a `f64` exactly and are rounded;
+/// - a date and a datetime give the datetime;
+/// - `Null` gives the other type.
+///
+/// Any other pair, such as a string and an integer, is an error.
+pub fn widest_common_type(left: &DataType, right: &DataType) -> Result<DataType, ReadError> {
+    if left == right {
+        return Ok(left.clone());
+    }
+    let widest = match (left, right) {
+        (DataType::Null, other) | (other, DataType::Null) => Some(other.clone()),
+        (DataType::Date, DataType::Datetime(unit, tz))
+        | (DataType::Datetime(unit, tz), DataType::Date) => {
+            Some(DataType::Datetime(*unit, tz.clone()))
+        }
+        _ => match (integer_width(left), integer_width(right)) {
+            (Some((l_signed, l_bits)), Some((r_signed, r_bits))) => {
+                if l_signed == r_signed {
+                    integer_type(l_signed, l_bits.max(r_bits))
+                } else {
+                    let (signed_bits, unsigned_bits) = if l_signed {
+                        (l_bits, r_bits)
+                    } else {
+                        (r_bits, l_bits)
+                    };
+                    integer_type(true, signed_bits.max(unsigned_bits * 2))
+                }
+            }
+            (Some((_, bits)), None) | (None, Some((_, bits))) => {
+                let float = if integer_width(left).is_some() {
+                    right
+                } else {
+                    left
+                };
+                match float {
+                    DataType::Float32 if bits <= 16 => Some(DataType::Float32),
+                    DataType::Float32 | DataType::Float64 => Some(DataType::Float64),
+                    _ => None,
+                }
+            }
+            (None, None) => match (left, right) {
+                (DataType::Float32, DataType::Float64) | (DataType::Float64, DataType::Float32) => {
+                    Some(DataType::Float64)
+                }
+                _ => None,
+            },
+        },
+    };
+    widest.ok_or_else(|| format!("No common type for {left} and {right}").into())
+}
+
+/// Signedness and width of an integer type.
+fn integer_width(dtype: &DataType) -> Option<(bool, u32)> {
+    match dtype {
+        DataType::UInt8 => Some((false, 8)),
+        DataType::UInt16 => Some((false, 16)),
+        DataType::UInt32 => Some((false, 32)),
+        DataType::UInt64 => Some((false, 64)),
+        DataType::UInt128 => Some((false, 128)),
+        DataType::Int8 => Some((true, 8)),
+        DataType::Int16 => Some((true, 16)),
+        DataType::Int32 => Some((true, 32)),
+        DataType::Int64 => Some((true, 64)),
+        DataType::Int128 => Some((true, 128)),
+        _ => None,
+    }
+}
+
+fn integer_type(signed: bool, bits: u32) -> Option<DataType> {
+    match (signed, bits) {
+        (false, 8) => Some(DataType::UInt8),
+        (false, 16) => Some(DataType::UInt16),
+        (false, 32) => Some(DataType::UInt32),
+        (false, 64) => Some(DataType::UInt64),
+        (false, 128) => Some(DataType::UInt128),
+        (true, 8) => Some(DataType::Int8),
+        (true, 16) => Some(DataType::Int16),
+        (true, 32) => Some(DataType::Int32),
+        (true, 64) => Some(DataType::Int64),
+        (true, 128) => Some(DataType::Int128),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars_core::prelude::TimeUnit;
+
+    #[test]
+    fn integers_widen_to_the_larger_width() {
+        assert_eq!(
+            widest_common_type(&DataType::Int8, &DataType::Int32).unwrap(),
+            DataType::Int32
+        );
+        assert_eq!(
+            widest_common_type(&DataType::UInt64, &DataType::UInt16).unwrap(),
+            DataType::UInt64
+        );
+    }
+
+    #[test]
+    fn mixed_signedness_widens_past_the_unsigned_width() {
+        assert_eq!(
+            widest_common_type(&DataType::UInt8, &DataType::Int8).unwrap(),
+            DataType::Int16
+        );
+        assert_eq!(
+            widest_common_type(&DataType::Int64, &DataType::UInt32).unwrap(),
+            DataType::Int64
+        );
+        assert!(widest_common_type(&DataType::UInt128, &DataType::Int8).is_err());
+    }
+
+    #[test]
+    fn integers_and_floats_widen_to_a_float() {
+        assert_eq!(
+            widest_common_type(&DataType::Int16, &DataType::Float32).unwrap(),
+            DataType::Float32
+        );
+        assert_eq!(
+            widest_common_type(&DataType::Float32, &DataType::Int32).unwrap(),
+            DataType::Float64
+        );
+        assert_eq!(
+            widest_common_type(&DataType::Float32, &DataType::Float64).unwrap(),
+            DataType::Float64
+        );
+    }
+
+    #[test]
+    fn dates_nulls_and_unrelated_types() {
+        let datetime = DataType::Datetime(TimeUnit::Milliseconds, None);
+        assert_eq!(
+            widest_common_type(&DataType::Date, &datetime).unwrap(),
+            datetime
+        );
+        assert_eq!(
+            widest_common_type(&DataType::Null, &DataType::String).unwrap(),
+            DataType::String
+        );
+        assert!(widest_common_type(&DataType::String, &DataType::Int64).is_err());
+    }
+}
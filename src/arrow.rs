@@ -0,0 +1,38 @@
+use crate::error::ReadError;
+use crate::get_lazy_frames_with_options;
+use crate::options::ReadOptions;
+use polars::prelude::LazyFrame;
+use polars_arrow::record_batch::RecordBatch;
+use polars_core::prelude::CompatLevel;
+use qa_settings::Comparable;
+
+/// Reads both sides and exports them as Arrow record batches, one per chunk,
+/// for Arrow-based consumers. Field names and types follow the resolved
+/// schema.
+///
+/// The batches are `polars_arrow` types, not those of the `arrow` crate.
+/// Hand them to `arrow` consumers through the Arrow C data interface.
+pub fn get_record_batches(
+    comp: &Comparable,
+) -> Result<(Vec<RecordBatch>, Vec<RecordBatch>), ReadError> {
+    get_record_batches_with_options(comp, &ReadOptions::default())
+}
+
+/// Reads both sides with `options` and exports them as Arrow record batches.
+/// Each side is collected in full before it is exported, so both sides must
+/// fit in memory; the batches share the collected buffers without copying.
+pub fn get_record_batches_with_options(
+    comp: &Comparable,
+    options: &ReadOptions,
+) -> Result<(Vec<RecordBatch>, Vec<RecordBatch>), ReadError> {
+    let (left, right) = get_lazy_frames_with_options(comp, options)?;
+    Ok((record_batches(left)?, record_batches(right)?))
+}
+
+/// The record batches of a collected frame, whose columns may be chunked
+/// differently and are aligned first.
+fn record_batches(lf: LazyFrame) -> Result<Vec<RecordBatch>, ReadError> {
+    let mut df = lf.collect()?;
+    df.align_chunks_par();
+    Ok(df.iter_chunks(CompatLevel::newest(), false).collect())
+}
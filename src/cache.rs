@@ -0,0 +1,164 @@
+use crate::error::ReadError;
+use crate::report::ReadReport;
+use polars_core::prelude::DataFrame;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Read as _;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// An in-process cache of read results, shared by clones, so a file compared
+/// against many others is only read once per run.
+///
+/// Entries are keyed by a hash of the file's content, along with its size,
+/// modification time and the reader settings, so looking a file up reads it
+/// through once. The oldest entries are evicted first once either limit is
+/// exceeded.
+#[derive(Clone)]
+pub struct ReadCache {
+    inner: Arc<Mutex<Entries>>,
+}
+
+#[derive(Default)]
+struct Entries {
+    max_entries: usize,
+    max_bytes: Option<usize>,
+    bytes: usize,
+    order: VecDeque<u64>,
+    frames: HashMap<u64, (DataFrame, ReadReport)>,
+}
+
+impl ReadCache {
+    /// A cache holding at most `max_entries` frames.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Entries {
+                max_entries,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Also limits the estimated in-memory size of the cached frames.
+    pub fn with_max_bytes(self, max_bytes: usize) -> Self {
+        self.entries().max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Drops every cached frame.
+    pub fn clear(&self) {
+        let mut entries = self.entries();
+        entries.order.clear();
+        entries.frames.clear();
+        entries.bytes = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries().frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Identifies `file` as it is now, read with settings described by
+    /// `settings`.
+    pub(crate) fn key(file: &Path, settings: &str) -> Result<u64, ReadError> {
+        let metadata = std::fs::metadata(file)?;
+        let mut hasher = DefaultHasher::new();
+        file.hash(&mut hasher);
+        settings.hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+        metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .hash(&mut hasher);
+        // streamed, so large files are never held in memory
+        let mut content = std::fs::File::open(file)?;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let n = content.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.write(&buf[..n]);
+        }
+        Ok(hasher.finish())
+    }
+
+    pub(crate) fn get(&self, key: u64) -> Option<(DataFrame, ReadReport)> {
+        self.entries().frames.get(&key).cloned()
+    }
+
+    pub(crate) fn insert(&self, key: u64, df: DataFrame, report: ReadReport) {
+        let mut entries = self.entries();
+        if entries.max_entries == 0 {
+            return;
+        }
+        let size = df.estimated_size();
+        if let Some((old, _)) = entries.frames.insert(key, (df, report)) {
+            entries.bytes -= old.estimated_size();
+            entries.order.retain(|k| *k != key);
+        }
+        entries.bytes += size;
+        entries.order.push_back(key);
+        while entries.frames.len() > entries.max_entries
+            || entries.max_bytes.is_some_and(|max| entries.bytes > max)
+        {
+            let Some(oldest) = entries.order.pop_front() else {
+                break;
+            };
+            if let Some((df, _)) = entries.frames.remove(&oldest) {
+                entries.bytes -= df.estimated_size();
+            }
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for ReadCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries();
+        f.debug_struct("ReadCache")
+            .field("max_entries", &entries.max_entries)
+            .field("max_bytes", &entries.max_bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn key_changes_with_the_content_alone() {
+        let path = crate::tests::temp_file("cache-key.csv", b"a,1\n");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let touch = || {
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap()
+        };
+        touch();
+        let before = ReadCache::key(&path, "settings").unwrap();
+        assert_eq!(ReadCache::key(&path, "settings").unwrap(), before);
+        assert_ne!(ReadCache::key(&path, "other").unwrap(), before);
+        // same size and modification time, other content
+        std::fs::write(&path, b"b,1\n").unwrap();
+        touch();
+        assert_ne!(ReadCache::key(&path, "settings").unwrap(), before);
+    }
+}
@@ -1,68 +1,506 @@
+use crate::error::ReadError;
+use crate::normalize;
+use crate::options::{BinaryEncoding, DateSystem, InvalidValuePolicy, ReadOptions};
+use base64::Engine as _;
 use calamine::Data;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use phf::phf_map;
 use polars_core::datatypes::AnyValue;
-use polars_core::prelude::DataType;
-use std::fmt::Error;
+use polars_core::prelude::{Categories, DataType, TimeUnit};
+use std::collections::BTreeMap;
+use std::sync::{RwLock, RwLockReadGuard};
 
+/// Canonical type names, lowercase and without spaces. Look types up with
+/// [`parse_type`], which normalizes the name first.
 pub static DT_CONV_MAP: phf::Map<&'static str, DataType> = phf_map! {
-    "null" | "Null" | "NULL" | "x" | "X" | "remove" | "Remove" => DataType::Null,
-    "bool" | "Bool" | "Boolean" | "boolean" | "BOOL" | "BOOLEAN"  => DataType::Boolean,
-    "u8" | "U8" | "uint8" | "UInt8" | "UINT8" | "bit" | "Bit" | "BIT"  => DataType::UInt8,
-    "u16" | "U16" | "uint16" | "UInt16" | "UINT16"  => DataType::UInt16,
-    "u32" | "U32" | "uint32" | "UInt32" | "UINT32" | "Int" | "INT" | "integer" | "Integer" | "INTEGER"  => DataType::UInt32,
-    "u64" | "U64" | "uint64" | "UInt64" | "UINT64" => DataType::UInt64,
-    "u128" | "U128" | "uint128" | "UInt128" | "UINT128"  => DataType::UInt128,
-    "i8" | "I8" | "int8" | "Int8" | "INT8" | "tinyint" | "TinyInt"  => DataType::Int8,
-    "i16" | "I16" | "int16" | "Int16" | "INT16"  => DataType::Int16,
-    "i32" | "I32" | "int32" | "Int32" | "INT32"  => DataType::Int32,
-    "i64" | "I64" | "int64" | "Int64" | "INT64"  => DataType::Int64,
-    "i128" | "I128" | "int128" | "Int128" | "INT128"  => DataType::Int128,
-    "f32" | "F32" | "float32" | "Float32" | "FLOAT32"  => DataType::Float32,
-    "f64" | "F64" | "float64" | "Float64" | "FLOAT64" | "float" | "Float" | "FLOAT" | "decimal" | "Decimal" | "DECIMAL"  => DataType::Float64,
-    "str" | "Str" | "string" | "String" | "STRING" | "TEXT"  => DataType::String,
-    "date" | "Date" | "DATE"  => DataType::Date,
+    "null" | "x" | "remove" => DataType::Null,
+    "bool" | "boolean" => DataType::Boolean,
+    "u8" | "uint8" | "bit" => DataType::UInt8,
+    "u16" | "uint16" => DataType::UInt16,
+    "u32" | "uint32" | "int" | "integer" => DataType::UInt32,
+    "u64" | "uint64" => DataType::UInt64,
+    "u128" | "uint128" => DataType::UInt128,
+    "i8" | "int8" | "tinyint" => DataType::Int8,
+    "i16" | "int16" => DataType::Int16,
+    "i32" | "int32" => DataType::Int32,
+    "i64" | "int64" => DataType::Int64,
+    "i128" | "int128" => DataType::Int128,
+    "f32" | "float32" => DataType::Float32,
+    "f64" | "float64" | "float" | "decimal" => DataType::Float64,
+    "str" | "string" | "text" => DataType::String,
+    "date" => DataType::Date,
+    "datetime" | "timestamp" => DataType::Datetime(TimeUnit::Milliseconds, None),
+    "time" => DataType::Time,
+    "duration" => DataType::Duration(TimeUnit::Milliseconds),
+    "binary" | "bytes" => DataType::Binary,
 };
 
+/// Resolves a schema type name case-insensitively and ignoring spaces, so
+/// `UInt32`, `uint32` and `U Int 32` are the same type.
+pub fn parse_type(type_str: &str) -> Option<DataType> {
+    let normalized = normalize_type_name(type_str);
+    if let Some(dtype) = read_aliases().get(&normalized) {
+        return Some(dtype.clone());
+    }
+    builtin_type(&normalized)
+}
+
+/// Type aliases added by [`register_type_alias`], by normalized name.
+static TYPE_ALIASES: RwLock<BTreeMap<String, DataType>> = RwLock::new(BTreeMap::new());
+
+fn read_aliases() -> RwLockReadGuard<'static, BTreeMap<String, DataType>> {
+    TYPE_ALIASES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Lowercases a type name and drops its spaces.
+fn normalize_type_name(type_str: &str) -> String {
+    type_str
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// The built-in type of a normalized type name.
+fn builtin_type(normalized: &str) -> Option<DataType> {
+    if let Some((precision, scale)) = decimal_type(normalized) {
+        return Some(DataType::Decimal(Some(precision), Some(scale)));
+    }
+    runtime_type(normalized).or_else(|| DT_CONV_MAP.get(normalized).cloned())
+}
+
+/// Makes `alias` a schema type name for `dtype` in every read of the
+/// process, as in `register_type_alias("money", DataType::Decimal(Some(18),
+/// Some(2)))`. Aliases are matched like built-in names, ignoring case and
+/// spaces. Registering an alias again replaces its type; an alias that is
+/// a built-in name is rejected.
+pub fn register_type_alias(alias: &str, dtype: DataType) -> Result<(), ReadError> {
+    let normalized = normalize_type_name(alias);
+    if normalized.is_empty() {
+        return Err("A type alias can't be empty".into());
+    }
+    if let Some(builtin) = builtin_type(&normalized) {
+        return Err(format!("Type alias '{alias}' is already the built-in type {builtin}").into());
+    }
+    TYPE_ALIASES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(normalized, dtype);
+    Ok(())
+}
+
+/// Days from 1899-12-30, day zero of Excel's 1900 date system once its
+/// phantom 1900-02-29 is accounted for, to 1970-01-01.
+const EXCEL_EPOCH_DAYS: f64 = 25569.0;
+
+/// Days from 1904-01-01, day zero of the 1904 date system, to 1970-01-01.
+const EXCEL_1904_EPOCH_DAYS: f64 = 24107.0;
+
+const DAY_NANOS: f64 = 86_400_000_000_000.0;
+
+/// Nanoseconds in `unit`, truncating toward zero.
+fn from_nanos(nanos: i64, unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Nanoseconds => nanos,
+        TimeUnit::Microseconds => nanos / 1_000,
+        TimeUnit::Milliseconds => nanos / 1_000_000,
+    }
+}
+
+/// Parses date text in ISO 8601 form or the configured date format into a
+/// `Date` or `Datetime` value.
+fn parse_date_text(
+    text: &str,
+    dtype: &DataType,
+    options: &ReadOptions,
+) -> Option<AnyValue<'static>> {
+    let text = text.trim();
+    let formats = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+    let datetime = formats
+        .iter()
+        .copied()
+        .chain(options.date_format())
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d"]
+                .into_iter()
+                .chain(options.date_format())
+                .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    match dtype {
+        DataType::Date => Some(AnyValue::Date(datetime.date().to_epoch_days())),
+        DataType::Datetime(unit, _) => {
+            let utc = datetime.and_utc();
+            let value = match unit {
+                TimeUnit::Milliseconds => utc.timestamp_millis(),
+                TimeUnit::Microseconds => utc.timestamp_micros(),
+                TimeUnit::Nanoseconds => utc.timestamp_nanos_opt()?,
+            };
+            Some(AnyValue::Datetime(value, *unit, None))
+        }
+        _ => None,
+    }
+}
+
+/// Parses ISO 8601 time text, alone or as the time of a date, into a `Time`
+/// value.
+fn parse_time_text(text: &str) -> Option<AnyValue<'static>> {
+    let text = text.trim();
+    let time = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|datetime| datetime.time())
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S%.f"))
+        .ok()?;
+    let since_midnight = time.signed_duration_since(NaiveTime::MIN);
+    Some(AnyValue::Time(since_midnight.num_nanoseconds()?))
+}
+
+/// The length in nanoseconds of an ISO 8601 duration such as `PT1H30M` or
+/// `-P1DT0.5S`. `None` for text that is not a duration and for years and
+/// months, which have no fixed length.
+pub fn iso_duration_nanos(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let text = text.strip_prefix(['P', 'p'])?;
+    let (date, time) = match text.split_once(['T', 't']) {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (text, None),
+    };
+    let (date, present) = duration_seconds(date, &[('W', 604_800.0), ('D', 86_400.0)])?;
+    let (time, time_present) = match time {
+        Some(time) => duration_seconds(time, &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)])?,
+        None => (0.0, false),
+    };
+    let nanos = (date + time) * 1e9;
+    if !(present || time_present) || !nanos.is_finite() || nanos.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let nanos = nanos.round() as i64;
+    Some(if negative { -nanos } else { nanos })
+}
+
+/// Sums the seconds of the `units` of a duration's date or time part, which
+/// must come in order, and tells whether any was present.
+fn duration_seconds(part: &str, units: &[(char, f64)]) -> Option<(f64, bool)> {
+    let mut rest = part;
+    let mut total = 0.0;
+    let mut present = false;
+    for &(unit, seconds) in units {
+        if let Some((number, after)) = rest.split_once([unit, unit.to_ascii_lowercase()]) {
+            total += number.replace(',', ".").parse::<f64>().ok()? * seconds;
+            rest = after;
+            present = true;
+        }
+    }
+    rest.is_empty().then_some((total, present))
+}
+
+/// Pushes a parsed value, or handles `text` as invalid for `dtype` when it
+/// didn't parse.
+fn push_parsed(
+    parsed: Option<AnyValue<'static>>,
+    text: &str,
+    dtype: &DataType,
+    column: &mut Vec<AnyValue>,
+    options: &ReadOptions,
+) -> Result<(), ReadError> {
+    match parsed {
+        Some(value) => column.push(value),
+        None if options.invalid_values() == InvalidValuePolicy::Null => {
+            column.push(AnyValue::Null);
+        }
+        None => return Err(cast_failed(text, dtype)),
+    }
+    Ok(())
+}
+
+/// Pushes a number cell, shown as `text`, in an integer column. Values out
+/// of the column's range and floats with a fraction are an error whatever the
+/// [`InvalidValuePolicy`], since nulling them would hide lost data.
+fn push_integer(text: &str, dtype: &DataType, column: &mut Vec<AnyValue>) -> Result<(), ReadError> {
+    let value = normalize::parse_number(text, dtype).ok_or_else(|| cast_failed(text, dtype))?;
+    column.push(value);
+    Ok(())
+}
+
+/// Converts a duration in nanoseconds to `dtype`: a duration, a time of day
+/// below one day, whole seconds for integers, seconds for floats.
+fn from_duration_nanos(nanos: i64, dtype: &DataType) -> Option<AnyValue<'static>> {
+    match dtype {
+        DataType::Duration(unit) => Some(AnyValue::Duration(from_nanos(nanos, *unit), *unit)),
+        DataType::Time => (0..DAY_NANOS as i64)
+            .contains(&nanos)
+            .then_some(AnyValue::Time(nanos)),
+        DataType::Float32 => Some(AnyValue::Float32(nanos as f32 / 1e9)),
+        DataType::Float64 => Some(AnyValue::Float64(nanos as f64 / 1e9)),
+        dtype if normalize::is_integer(dtype) && nanos % 1_000_000_000 == 0 => {
+            normalize::parse_number(&(nanos / 1_000_000_000).to_string(), dtype)
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the text of a binary cell. `None` when it is not valid `encoding`.
+fn decode_binary(text: &str, encoding: BinaryEncoding) -> Option<Vec<u8>> {
+    match encoding {
+        BinaryEncoding::Raw => Some(text.as_bytes().to_vec()),
+        BinaryEncoding::Hex => {
+            let text = text.trim().as_bytes();
+            if text.len() % 2 != 0 {
+                return None;
+            }
+            text.chunks(2)
+                .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+                .collect()
+        }
+        BinaryEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(text.trim())
+            .ok(),
+    }
+}
+
+fn cast_failed(value: impl ToString, dtype: &DataType) -> ReadError {
+    ReadError::CastFailed {
+        value: value.to_string(),
+        dtype: dtype.clone(),
+    }
+}
+
+/// Pushes the value of an Excel serial in a temporal column, which is
+/// invalid when it doesn't decode.
+fn push_serial(
+    serial: f64,
+    dtype: &DataType,
+    column: &mut Vec<AnyValue>,
+    options: &ReadOptions,
+) -> Result<(), ReadError> {
+    match from_serial(serial, dtype, options.date_system()) {
+        Some(value) => column.push(value),
+        None if options.invalid_values() == InvalidValuePolicy::Null => {
+            column.push(AnyValue::Null);
+        }
+        None => return Err(cast_failed(serial, dtype)),
+    }
+    Ok(())
+}
+
+/// Decodes an Excel serial, days since the epoch with the time of day as the
+/// fraction, into `dtype`. `None` for non-temporal types and negative or
+/// non-finite serials.
+fn from_serial(
+    serial: f64,
+    dtype: &DataType,
+    date_system: DateSystem,
+) -> Option<AnyValue<'static>> {
+    if !serial.is_finite() || serial < 0.0 {
+        return None;
+    }
+    // a duration serial is a number of days, not a point in time
+    if let DataType::Duration(unit) = dtype {
+        let nanos = serial * DAY_NANOS;
+        return (nanos < i64::MAX as f64)
+            .then(|| AnyValue::Duration(from_nanos(nanos.round() as i64, *unit), *unit));
+    }
+    let days = match date_system {
+        // serials before the phantom leap day are one day ahead of the epoch
+        DateSystem::Excel1900 if serial < 60.0 => serial + 1.0 - EXCEL_EPOCH_DAYS,
+        DateSystem::Excel1900 => serial - EXCEL_EPOCH_DAYS,
+        DateSystem::Excel1904 => serial - EXCEL_1904_EPOCH_DAYS,
+    };
+    match dtype {
+        DataType::Date => Some(AnyValue::Date(days.floor() as i32)),
+        DataType::Datetime(unit, _) => {
+            let nanos = (days * DAY_NANOS).round() as i64;
+            Some(AnyValue::Datetime(from_nanos(nanos, *unit), *unit, None))
+        }
+        // a time of day is the fraction of a day, the date of a datetime is dropped
+        DataType::Time => Some(AnyValue::Time(
+            ((serial.fract() * DAY_NANOS).round() as i64).min(DAY_NANOS as i64 - 1),
+        )),
+        _ => None,
+    }
+}
+
+/// Every type name [`parse_type`] accepts, for error messages.
+pub fn accepted_types() -> Vec<String> {
+    let mut names = DT_CONV_MAP
+        .keys()
+        .chain(&["categorical", "cat", "decimal(p,s)", "str(n)"])
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    names.extend(read_aliases().keys().cloned());
+    names.sort_unstable();
+    names
+}
+
+/// Types that can't live in [`DT_CONV_MAP`] because they are built at
+/// runtime, such as categoricals sharing the global category mapping.
+pub fn runtime_type(type_str: &str) -> Option<DataType> {
+    match type_str.trim().to_lowercase().as_str() {
+        "categorical" | "cat" => Some(DataType::from_categories(Categories::global())),
+        _ => None,
+    }
+}
+
+/// The precision and scale of a decimal type name, as in `decimal(18,2)`.
+pub fn decimal_type(type_str: &str) -> Option<(usize, usize)> {
+    let (base, rest) = type_str.split_once('(')?;
+    if !matches!(base.trim().to_lowercase().as_str(), "decimal" | "numeric") {
+        return None;
+    }
+    let (precision, scale) = rest.strip_suffix(')')?.split_once(',')?;
+    let precision = precision.trim().parse().ok()?;
+    let scale = scale.trim().parse().ok()?;
+    (scale <= precision).then_some((precision, scale))
+}
+
+/// Scales decimal text such as `-12.345` to an integer with `scale`
+/// fractional digits, rounding half away from zero: `1.005` at scale 2 is
+/// `101`. `None` when the text is not a plain decimal number or needs more
+/// than `precision` digits.
+pub fn scale_decimal(text: &str, precision: usize, scale: usize) -> Option<i128> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let kept = fraction.get(..scale.min(fraction.len())).unwrap_or("");
+    let mut value: i128 = 0;
+    for c in whole.chars().chain(kept.chars()) {
+        value = value
+            .checked_mul(10)?
+            .checked_add(c.to_digit(10)? as i128)?;
+    }
+    for _ in kept.len()..scale {
+        value = value.checked_mul(10)?;
+    }
+    if fraction.as_bytes().get(scale).is_some_and(|d| *d >= b'5') {
+        value = value.checked_add(1)?;
+    }
+    if value.checked_abs()? >= 10i128.checked_pow(precision as u32)? {
+        return None;
+    }
+    Some(if negative { -value } else { value })
+}
+
+/// Pushes decimal text into a decimal column, which is invalid when it
+/// doesn't fit.
+fn push_decimal(
+    text: &str,
+    precision: usize,
+    scale: usize,
+    column: &mut Vec<AnyValue>,
+    options: &ReadOptions,
+) -> Result<(), ReadError> {
+    match scale_decimal(text, precision, scale) {
+        Some(value) => column.push(AnyValue::Decimal(value, scale)),
+        None if options.invalid_values() == InvalidValuePolicy::Null => {
+            column.push(AnyValue::Null);
+        }
+        None => {
+            return Err(cast_failed(
+                text,
+                &DataType::Decimal(Some(precision), Some(scale)),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Splits a fixed length off a type name, as in `str(10)`.
+pub fn split_length(type_str: &str) -> (&str, Option<usize>) {
+    if let Some((base, rest)) = type_str.split_once('(')
+        && let Some(length) = rest.strip_suffix(')')
+        && let Ok(length) = length.trim().parse::<usize>()
+    {
+        return (base.trim_end(), Some(length));
+    }
+    (type_str, None)
+}
+
+/// The type inferred for a column of Excel cells: `Float64` when every
+/// non-empty cell holds a number, as a value or as text, and `String`
+/// otherwise.
+pub fn infer_type<'a>(cells: impl IntoIterator<Item = &'a Data>) -> DataType {
+    let mut numeric = false;
+    for cell in cells {
+        match cell {
+            Data::Empty => {}
+            Data::Int(_) | Data::Float(_) => numeric = true,
+            Data::String(s) if s.trim().parse::<f64>().is_ok() => numeric = true,
+            _ => return DataType::String,
+        }
+    }
+    if numeric {
+        DataType::Float64
+    } else {
+        DataType::String
+    }
+}
+
+fn is_temporal(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::Date | DataType::Datetime(_, _) | DataType::Time | DataType::Duration(_)
+    )
+}
+
 pub fn cast_excel_type_to_polars_type(
     value: &calamine::Data,
     dtype: &DataType,
     column: &mut Vec<AnyValue>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    options: &ReadOptions,
+) -> Result<(), ReadError> {
+    if let DataType::Decimal(precision, scale) = dtype {
+        let text = match value {
+            Data::String(s) => Some(normalize::numeric_text(s, options).into_owned()),
+            Data::Int(i) => Some(i.to_string()),
+            // Display never uses an exponent and is the shortest exact text
+            Data::Float(f) => Some(f.to_string()),
+            _ => None,
+        };
+        if let Some(text) = text {
+            return push_decimal(
+                &text,
+                precision.unwrap_or(38),
+                scale.unwrap_or(0),
+                column,
+                options,
+            );
+        }
+    }
     match value {
         calamine::Data::Empty => {
             column.push(AnyValue::Null);
         }
+        // numbers and other cells are binary as their text
+        value if dtype == &DataType::Binary => {
+            let text = value.to_string();
+            let bytes = decode_binary(&text, options.binary_encoding()).map(AnyValue::BinaryOwned);
+            push_parsed(bytes, &text, dtype, column, options)?;
+        }
+        Data::Int(i) if is_temporal(dtype) => {
+            push_serial(*i as f64, dtype, column, options)?;
+        }
+        Data::Int(i) if normalize::is_integer(dtype) => {
+            push_integer(&i.to_string(), dtype, column)?;
+        }
         Data::Int(i) => match dtype {
-            DataType::UInt8 => {
-                column.push(AnyValue::UInt8(*i as u8));
-            }
-            DataType::UInt16 => {
-                column.push(AnyValue::UInt16(*i as u16));
-            }
-            DataType::UInt32 => {
-                column.push(AnyValue::UInt32(*i as u32));
-            }
-            DataType::UInt64 => {
-                column.push(AnyValue::UInt64(*i as u64));
-            }
-            DataType::UInt128 => {
-                column.push(AnyValue::UInt128(*i as u128));
-            }
-            DataType::Int8 => {
-                column.push(AnyValue::Int8(*i as i8));
-            }
-            DataType::Int16 => {
-                column.push(AnyValue::Int16(*i as i16));
-            }
-            DataType::Int32 => {
-                column.push(AnyValue::Int32(*i as i32));
-            }
-            DataType::Int64 => {
-                column.push(AnyValue::Int64(*i as i64));
-            }
-            DataType::Int128 => {
-                column.push(AnyValue::Int128(*i as i128));
-            }
             DataType::Boolean => {
                 column.push(AnyValue::Boolean(*i != 0));
             }
@@ -72,26 +510,88 @@ pub fn cast_excel_type_to_polars_type(
             DataType::Float64 => {
                 column.push(AnyValue::Float64(*i as f64));
             }
-            val => {
-                panic!("Mismatched data type for Int value: {val}");
+            DataType::String | DataType::Categorical(_, _) => {
+                column.push(AnyValue::StringOwned(i.to_string().into()));
+            }
+            _ => {
+                push_parsed(None, &i.to_string(), dtype, column, options)?;
             }
         },
+        Data::Float(f) if is_temporal(dtype) => {
+            push_serial(*f, dtype, column, options)?;
+        }
+        // whole floats are integers, Excel stores most integers as floats
+        Data::Float(f) if normalize::is_integer(dtype) => {
+            push_integer(&f.to_string(), dtype, column)?;
+        }
         Data::Float(f) => {
             column.push(AnyValue::Float64(*f));
         }
+        Data::String(s) if normalize::is_numeric(dtype) => {
+            match normalize::parse_number(&normalize::numeric_text(s, options), dtype) {
+                Some(value) => column.push(value),
+                None if options.invalid_values() == InvalidValuePolicy::Null => {
+                    column.push(AnyValue::Null);
+                }
+                None => {
+                    return Err(cast_failed(s, dtype));
+                }
+            }
+        }
+        Data::String(s) if matches!(dtype, DataType::Date | DataType::Datetime(_, _)) => {
+            match parse_date_text(s, dtype, options) {
+                Some(value) => column.push(value),
+                None if options.invalid_values() == InvalidValuePolicy::Null => {
+                    column.push(AnyValue::Null);
+                }
+                None => {
+                    return Err(cast_failed(s, dtype));
+                }
+            }
+        }
+        Data::String(s) if matches!(dtype, DataType::Duration(_)) => {
+            let parsed = iso_duration_nanos(s).and_then(|nanos| from_duration_nanos(nanos, dtype));
+            push_parsed(parsed, s, dtype, column, options)?;
+        }
+        Data::String(s) if dtype == &DataType::Boolean => match options.parse_bool(s) {
+            Some(b) => column.push(AnyValue::Boolean(b)),
+            None if options.invalid_values() == InvalidValuePolicy::Null => {
+                column.push(AnyValue::Null);
+            }
+            None => {
+                return Err(cast_failed(s, dtype));
+            }
+        },
         Data::String(s) => {
             column.push(AnyValue::StringOwned(s.into()));
         }
         Data::Bool(b) => {
             column.push(AnyValue::Boolean(*b));
         }
-        Data::DateTime(dt) => match dt.as_datetime().map(|val| val.date()) {
-            Some(date) => {
-                column.push(AnyValue::Date(date.to_epoch_days()));
+        Data::DateTime(dt) => match dtype {
+            DataType::Time | DataType::Duration(_) => {
+                push_serial(dt.as_f64(), dtype, column, options)?;
             }
-            None => {
-                column.push(AnyValue::Null);
+            DataType::Datetime(unit, _) => {
+                let utc = dt.as_datetime().map(|val| val.and_utc());
+                let value = utc.and_then(|utc| match unit {
+                    TimeUnit::Milliseconds => Some(utc.timestamp_millis()),
+                    TimeUnit::Microseconds => Some(utc.timestamp_micros()),
+                    TimeUnit::Nanoseconds => utc.timestamp_nanos_opt(),
+                });
+                match value {
+                    Some(value) => column.push(AnyValue::Datetime(value, *unit, None)),
+                    None => column.push(AnyValue::Null),
+                }
             }
+            _ => match dt.as_datetime().map(|val| val.date()) {
+                Some(date) => {
+                    column.push(AnyValue::Date(date.to_epoch_days()));
+                }
+                None => {
+                    column.push(AnyValue::Null);
+                }
+            },
         },
         Data::Error(e) => {
             #[cfg(debug_assertions)]
@@ -100,9 +600,372 @@ pub fn cast_excel_type_to_polars_type(
             }
             column.push(AnyValue::Null);
         }
+        Data::DateTimeIso(s) if dtype == &DataType::String => {
+            column.push(AnyValue::StringOwned(s.into()));
+        }
+        Data::DateTimeIso(s) if dtype == &DataType::Time => {
+            push_parsed(parse_time_text(s), s, dtype, column, options)?;
+        }
+        Data::DateTimeIso(s) => {
+            push_parsed(
+                parse_date_text(s, dtype, options),
+                s,
+                dtype,
+                column,
+                options,
+            )?;
+        }
+        Data::DurationIso(s) if dtype == &DataType::String => {
+            column.push(AnyValue::StringOwned(s.into()));
+        }
+        Data::DurationIso(s) => {
+            let parsed = iso_duration_nanos(s).and_then(|nanos| from_duration_nanos(nanos, dtype));
+            push_parsed(parsed, s, dtype, column, options)?;
+        }
+        // for variants added by later calamine versions
+        #[allow(unreachable_patterns)]
         _unknown_type => {
-            return Err(Box::new(Error {}));
+            return Err(cast_failed(format!("{value:?}"), dtype));
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_serial_takes_the_time_of_day() {
+        let time = |serial| from_serial(serial, &DataType::Time, DateSystem::Excel1900);
+        assert_eq!(time(0.5), Some(AnyValue::Time(43_200_000_000_000)));
+        assert_eq!(time(1.5), Some(AnyValue::Time(43_200_000_000_000)));
+        // 2025-01-01 06:00
+        assert_eq!(time(45658.25), Some(AnyValue::Time(21_600_000_000_000)));
+        assert_eq!(time(-0.5), None);
+    }
+
+    #[test]
+    fn datetime_cells_in_time_columns_keep_the_time_of_day() {
+        let options = ReadOptions::default();
+        let mut column = Vec::new();
+        let cell = Data::DateTime(calamine::ExcelDateTime::new(
+            45658.25,
+            calamine::ExcelDateTimeType::DateTime,
+            false,
+        ));
+        cast_excel_type_to_polars_type(&cell, &DataType::Time, &mut column, &options).unwrap();
+        assert_eq!(column, [AnyValue::Time(21_600_000_000_000)]);
+    }
+
+    /// Casts one cell to `dtype` with default options.
+    fn cast(value: Data, dtype: DataType) -> Result<Vec<AnyValue<'static>>, ReadError> {
+        let mut column = Vec::new();
+        cast_excel_type_to_polars_type(&value, &dtype, &mut column, &ReadOptions::default())?;
+        Ok(column.into_iter().map(AnyValue::into_static).collect())
+    }
+
+    #[test]
+    fn whole_floats_fill_integer_columns_and_fractions_fail() {
+        assert_eq!(
+            cast(Data::Float(3.0), DataType::Int32).unwrap(),
+            [AnyValue::Int32(3)]
+        );
+        assert!(cast(Data::Float(3.5), DataType::Int32).is_err());
+        assert!(cast(Data::Float(3e10), DataType::Int32).is_err());
+        // lost data is an error even when invalid values are nulled
+        let options = ReadOptions::default().with_invalid_values(InvalidValuePolicy::Null);
+        let mut column = Vec::new();
+        assert!(
+            cast_excel_type_to_polars_type(
+                &Data::Float(3.5),
+                &DataType::Int32,
+                &mut column,
+                &options
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn int_cells_fit_integer_columns_up_to_their_bounds() {
+        let bounds = [
+            (DataType::Int8, i8::MIN as i64, i8::MAX as i64),
+            (DataType::Int16, i16::MIN as i64, i16::MAX as i64),
+            (DataType::Int32, i32::MIN as i64, i32::MAX as i64),
+        ];
+        for (dtype, min, max) in bounds {
+            for i in [min, max] {
+                let value = cast(Data::Int(i), dtype.clone()).unwrap();
+                assert_eq!(value[0].extract::<i64>(), Some(i), "{dtype} {i}");
+            }
+            for i in [min - 1, max + 1] {
+                assert!(cast(Data::Int(i), dtype.clone()).is_err(), "{dtype} {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn int_cells_in_unsupported_columns_are_invalid() {
+        let list = DataType::List(Box::new(DataType::Int64));
+        assert_eq!(cast(Data::Int(5), list.clone()).unwrap(), [AnyValue::Null]);
+        let options = ReadOptions::default().with_invalid_values(InvalidValuePolicy::Error);
+        let mut column = Vec::new();
+        assert!(
+            cast_excel_type_to_polars_type(&Data::Int(5), &list, &mut column, &options).is_err()
+        );
+    }
+
+    #[test]
+    fn int_cells_in_text_columns_are_text() {
+        let options = ReadOptions::default();
+        let mut column = Vec::new();
+        cast_excel_type_to_polars_type(&Data::Int(42), &DataType::String, &mut column, &options)
+            .unwrap();
+        assert_eq!(column, [AnyValue::StringOwned("42".into())]);
+        assert!(
+            cast_excel_type_to_polars_type(
+                &Data::Int(300),
+                &DataType::UInt8,
+                &mut column,
+                &options
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_type_ignores_case_and_spaces() {
+        assert_eq!(parse_type("UInt32"), Some(DataType::UInt32));
+        assert_eq!(parse_type("U Int 32"), Some(DataType::UInt32));
+        assert_eq!(
+            parse_type("Datetime"),
+            Some(DataType::Datetime(TimeUnit::Milliseconds, None))
+        );
+        assert_eq!(
+            parse_type("decimal(18, 2)"),
+            Some(DataType::Decimal(Some(18), Some(2)))
+        );
+        assert_eq!(parse_type("decimal"), Some(DataType::Float64));
+        assert!(matches!(
+            parse_type("Cat"),
+            Some(DataType::Categorical(_, _))
+        ));
+        assert_eq!(parse_type("nonsense"), None);
+    }
+
+    #[test]
+    fn type_aliases_resolve_like_builtin_names() {
+        register_type_alias("test money", DataType::Decimal(Some(18), Some(2))).unwrap();
+        assert_eq!(
+            parse_type("TestMoney"),
+            Some(DataType::Decimal(Some(18), Some(2)))
+        );
+        assert!(accepted_types().contains(&"testmoney".to_string()));
+        assert!(register_type_alias("Int", DataType::Int64).is_err());
+        assert!(register_type_alias(" ", DataType::Int64).is_err());
+    }
+
+    #[test]
+    fn decimal_type_reads_precision_and_scale() {
+        assert_eq!(decimal_type("numeric(10, 4)"), Some((10, 4)));
+        assert_eq!(decimal_type("decimal(2,3)"), None);
+    }
+
+    #[test]
+    fn scale_decimal_rounds_half_away_from_zero() {
+        assert_eq!(scale_decimal("1.005", 5, 2), Some(101));
+        assert_eq!(scale_decimal("-12.345", 10, 2), Some(-1235));
+        assert_eq!(scale_decimal("9.99", 3, 1), Some(100));
+        assert_eq!(scale_decimal("12", 4, 2), Some(1200));
+        assert_eq!(scale_decimal("+7", 3, 0), Some(7));
+        assert_eq!(scale_decimal(".5", 3, 1), Some(5));
+    }
+
+    #[test]
+    fn scale_decimal_rejects_other_text_and_overflow() {
+        assert_eq!(scale_decimal("123", 4, 2), None);
+        assert_eq!(scale_decimal("1e5", 10, 0), None);
+        assert_eq!(scale_decimal("", 10, 0), None);
+        assert_eq!(scale_decimal("1,5", 10, 1), None);
+    }
+
+    #[test]
+    fn split_length_reads_fixed_length_strings() {
+        assert_eq!(split_length("str(10)"), ("str", Some(10)));
+        assert_eq!(split_length("str"), ("str", None));
+        assert_eq!(split_length("decimal(18,2)"), ("decimal(18,2)", None));
+    }
+
+    #[test]
+    fn decode_binary_reads_each_encoding() {
+        assert_eq!(
+            decode_binary("ab", BinaryEncoding::Raw),
+            Some(b"ab".to_vec())
+        );
+        assert_eq!(
+            decode_binary("0aFf", BinaryEncoding::Hex),
+            Some(vec![0x0a, 0xff])
+        );
+        assert_eq!(decode_binary("abc", BinaryEncoding::Hex), None);
+        assert_eq!(
+            decode_binary("aGk=", BinaryEncoding::Base64),
+            Some(b"hi".to_vec())
+        );
+        assert_eq!(decode_binary("!", BinaryEncoding::Base64), None);
+    }
+
+    #[test]
+    fn from_serial_decodes_1900_dates() {
+        let date = |serial| from_serial(serial, &DataType::Date, DateSystem::Excel1900);
+        assert_eq!(date(25569.0), Some(AnyValue::Date(0)));
+        // 2025-01-01
+        assert_eq!(date(45658.0), Some(AnyValue::Date(20089)));
+        // 1900-01-01, before the phantom leap day
+        assert_eq!(date(1.0), Some(AnyValue::Date(-25567)));
+        // 1900-03-01, after it
+        assert_eq!(date(61.0), Some(AnyValue::Date(-25508)));
+        assert_eq!(date(-1.0), None);
+        assert_eq!(date(f64::NAN), None);
+    }
+
+    #[test]
+    fn float_cells_in_date_columns_are_serials() {
+        assert_eq!(
+            cast(Data::Float(45658.0), DataType::Date).unwrap(),
+            [AnyValue::Date(20089)]
+        );
+    }
+
+    #[test]
+    fn from_serial_decodes_1904_dates() {
+        assert_eq!(
+            from_serial(0.0, &DataType::Date, DateSystem::Excel1904),
+            Some(AnyValue::Date(-24107))
+        );
+    }
+
+    #[test]
+    fn float_cells_follow_the_date_system() {
+        let options = ReadOptions::default().with_date_system(DateSystem::Excel1904);
+        let mut column = Vec::new();
+        // 2025-01-01 is 1462 days closer to the 1904 epoch
+        cast_excel_type_to_polars_type(
+            &Data::Float(44196.0),
+            &DataType::Date,
+            &mut column,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(column, [AnyValue::Date(20089)]);
+    }
+
+    #[test]
+    fn fractional_float_cells_are_times_and_datetimes() {
+        assert_eq!(
+            cast(Data::Float(0.25), DataType::Time).unwrap(),
+            [AnyValue::Time(21_600_000_000_000)]
+        );
+        let datetime = DataType::Datetime(TimeUnit::Milliseconds, None);
+        assert_eq!(
+            from_serial(25569.5, &datetime, DateSystem::Excel1900),
+            Some(AnyValue::Datetime(43_200_000, TimeUnit::Milliseconds, None))
+        );
+        assert_eq!(
+            from_serial(1.0, &DataType::Float64, DateSystem::Excel1900),
+            None
+        );
+    }
+
+    #[test]
+    fn datetime_cells_keep_their_time_of_day() {
+        // 2025-01-01 18:00
+        let cell = Data::DateTime(calamine::ExcelDateTime::new(
+            45658.75,
+            calamine::ExcelDateTimeType::DateTime,
+            false,
+        ));
+        assert_eq!(
+            cast(
+                cell.clone(),
+                DataType::Datetime(TimeUnit::Milliseconds, None)
+            )
+            .unwrap(),
+            [AnyValue::Datetime(
+                1_735_754_400_000,
+                TimeUnit::Milliseconds,
+                None
+            )]
+        );
+        assert_eq!(cast(cell, DataType::Date).unwrap(), [AnyValue::Date(20089)]);
+    }
+
+    #[test]
+    fn iso_duration_nanos_sums_the_units() {
+        assert_eq!(iso_duration_nanos("PT1H30M"), Some(5_400_000_000_000));
+        assert_eq!(iso_duration_nanos("-P1DT0.5S"), Some(-86_400_500_000_000));
+        assert_eq!(iso_duration_nanos("P2W"), Some(1_209_600_000_000_000));
+        assert_eq!(iso_duration_nanos("pt1m"), Some(60_000_000_000));
+    }
+
+    #[test]
+    fn iso_duration_nanos_rejects_other_text() {
+        assert_eq!(iso_duration_nanos("P1Y"), None);
+        assert_eq!(iso_duration_nanos("P1M"), None);
+        assert_eq!(iso_duration_nanos("PT"), None);
+        assert_eq!(iso_duration_nanos("P"), None);
+        assert_eq!(iso_duration_nanos("1H"), None);
+        assert_eq!(iso_duration_nanos("PT1M1H"), None);
+    }
+
+    #[test]
+    fn iso_cells_parse_into_temporal_columns() {
+        let datetime = DataType::Datetime(TimeUnit::Milliseconds, None);
+        assert_eq!(
+            cast(Data::DateTimeIso("2025-01-01T18:00:00".into()), datetime).unwrap(),
+            [AnyValue::Datetime(
+                1_735_754_400_000,
+                TimeUnit::Milliseconds,
+                None
+            )]
+        );
+        assert_eq!(
+            cast(
+                Data::DateTimeIso("2025-01-01T06:00:00".into()),
+                DataType::Time
+            )
+            .unwrap(),
+            [AnyValue::Time(21_600_000_000_000)]
+        );
+        assert_eq!(
+            cast(
+                Data::DurationIso("PT1H30M".into()),
+                DataType::Duration(TimeUnit::Milliseconds)
+            )
+            .unwrap(),
+            [AnyValue::Duration(5_400_000, TimeUnit::Milliseconds)]
+        );
+        assert_eq!(
+            cast(Data::DateTimeIso("not a date".into()), DataType::Date).unwrap(),
+            [AnyValue::Null]
+        );
+    }
+
+    #[test]
+    fn one_hour_reads_as_a_duration() {
+        let duration = DataType::Duration(TimeUnit::Milliseconds);
+        let hour = [AnyValue::Duration(3_600_000, TimeUnit::Milliseconds)];
+        assert_eq!(
+            cast(Data::Float(1.0 / 24.0), duration.clone()).unwrap(),
+            hour
+        );
+        assert_eq!(
+            cast(Data::String("PT1H".into()), duration.clone()).unwrap(),
+            hour
+        );
+        assert_eq!(
+            from_serial(1.5, &duration, DateSystem::Excel1900),
+            Some(AnyValue::Duration(129_600_000, TimeUnit::Milliseconds))
+        );
+    }
+}
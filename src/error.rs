@@ -0,0 +1,110 @@
+use polars::prelude::PolarsError;
+use polars_core::prelude::DataType;
+use std::convert::Infallible;
+
+/// Everything reading a [`Comparable`](qa_settings::Comparable) can fail
+/// with. Errors from the underlying libraries are kept as the source of
+/// their variant; problems found by the readers themselves have their own
+/// variants, or [`ReadError::Invalid`] when nothing more specific applies.
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    /// The `Comparable` declares no columns.
+    #[error("Read failed due to empty provided schema")]
+    EmptySchema,
+    /// A schema column's type name is not one the readers know.
+    #[error(
+        "Column '{column}' has unknown type '{type_name}', expected one of: {}",
+        crate::dtconv::accepted_types().join(", ")
+    )]
+    UnknownType { column: String, type_name: String },
+    /// The schema declares these column names more than once.
+    #[error("Schema declares duplicate columns: {}", .0.join(", "))]
+    DuplicateColumns(Vec<String>),
+    /// A header cell doesn't name the schema column at its position.
+    #[error(
+        "Header '{found}' at column {index} does not match expected schema column name '{expected}'"
+    )]
+    HeaderMismatch {
+        index: usize,
+        expected: String,
+        found: String,
+    },
+    /// A cell value can't be read as its column's type.
+    #[error("Cannot read '{value}' as {dtype}")]
+    CastFailed { value: String, dtype: DataType },
+    /// Reading a cell failed, at its 1-based `row` and `column` in the file,
+    /// sheet or table named by `location`.
+    #[error("{location} R{row}C{column}: {source}")]
+    AtCell {
+        location: String,
+        row: u32,
+        column: u32,
+        source: Box<ReadError>,
+    },
+    /// The workbook has no sheet or table of this name.
+    #[error("The workbook has no {what} named '{name}'")]
+    NotFound { what: &'static str, name: String },
+    /// A row holds more cells than the schema declares columns, or fewer when
+    /// short rows are errors.
+    #[error("Schema declares {expected} columns but the source row has {found} cells")]
+    ColumnCountMismatch { expected: usize, found: usize },
+    /// The csv separator, quote and end of line characters are not distinct.
+    #[error("Invalid csv settings: {0}")]
+    InvalidCsvConfig(String),
+    /// A csv record, quoted line breaks included, is longer than the limit.
+    #[error("Line {line} is longer than {limit} bytes, the file may have an unterminated quote")]
+    LineTooLong { line: usize, limit: u64 },
+    /// Settings, references or file contents the readers can't work with.
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Calamine(#[from] calamine::Error),
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+}
+
+impl From<String> for ReadError {
+    fn from(message: String) -> Self {
+        ReadError::Invalid(message)
+    }
+}
+
+impl From<&str> for ReadError {
+    fn from(message: &str) -> Self {
+        ReadError::Invalid(message.to_string())
+    }
+}
+
+impl From<calamine::XlsxError> for ReadError {
+    fn from(e: calamine::XlsxError) -> Self {
+        ReadError::Calamine(e.into())
+    }
+}
+
+impl From<calamine::XlsError> for ReadError {
+    fn from(e: calamine::XlsError) -> Self {
+        ReadError::Calamine(e.into())
+    }
+}
+
+impl From<calamine::XlsbError> for ReadError {
+    fn from(e: calamine::XlsbError) -> Self {
+        ReadError::Calamine(e.into())
+    }
+}
+
+impl From<calamine::OdsError> for ReadError {
+    fn from(e: calamine::OdsError) -> Self {
+        ReadError::Calamine(e.into())
+    }
+}
+
+impl From<Infallible> for ReadError {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
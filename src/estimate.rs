@@ -0,0 +1,76 @@
+use polars::prelude::Schema;
+use polars_core::prelude::DataType;
+
+/// Bytes assumed per string value: a 16 byte view plus a guess at the text
+/// stored out of line.
+const STRING_BYTES: usize = 32;
+
+/// Estimates the bytes a frame with `schema` and `rows` rows takes in memory.
+///
+/// Fixed width columns count their width plus a validity bit per row, string
+/// columns a flat [`STRING_BYTES`] per value. Categorical columns count their
+/// `u32` category index only: the dictionary of distinct values is left out,
+/// which underestimates columns with few repeats. `Null` columns are not read
+/// and count nothing. Meant for capacity planning, not exact accounting.
+pub fn estimate_memory(schema: &Schema, rows: usize) -> usize {
+    schema
+        .iter_values()
+        .map(|dtype| {
+            let validity = rows.div_ceil(8);
+            match dtype {
+                DataType::Null => 0,
+                DataType::Boolean => rows.div_ceil(8) + validity,
+                DataType::String | DataType::Binary => rows * STRING_BYTES + validity,
+                dtype => rows * width(dtype) + validity,
+            }
+        })
+        .sum()
+}
+
+/// Bytes per value of a fixed width type, [`STRING_BYTES`] for the rest.
+fn width(dtype: &DataType) -> usize {
+    match dtype {
+        DataType::UInt8 | DataType::Int8 => 1,
+        DataType::UInt16 | DataType::Int16 => 2,
+        DataType::UInt32
+        | DataType::Int32
+        | DataType::Float32
+        | DataType::Date
+        | DataType::Categorical(_, _) => 4,
+        DataType::UInt64
+        | DataType::Int64
+        | DataType::Float64
+        | DataType::Datetime(_, _)
+        | DataType::Duration(_)
+        | DataType::Time => 8,
+        DataType::UInt128 | DataType::Int128 | DataType::Decimal(_, _) => 16,
+        _ => STRING_BYTES,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::PlSmallStr;
+    use polars_core::prelude::Categories;
+
+    #[test]
+    fn estimate_sums_column_widths_and_validity() {
+        let schema = [
+            ("a", DataType::Int64),
+            ("s", DataType::String),
+            ("t", DataType::Time),
+            ("d", DataType::Decimal(Some(18), Some(2))),
+            ("c", DataType::from_categories(Categories::global())),
+            ("gone", DataType::Null),
+        ]
+        .into_iter()
+        .map(|(name, dtype)| (PlSmallStr::from(name), dtype))
+        .collect::<Schema>();
+        // 8 rows take one validity byte per column
+        assert_eq!(
+            estimate_memory(&schema, 8),
+            (8 * 8 + 1) + (8 * STRING_BYTES + 1) + (8 * 8 + 1) + (8 * 16 + 1) + (8 * 4 + 1)
+        );
+    }
+}
@@ -1,210 +1,2244 @@
+mod a1;
+mod align;
+mod arrow;
+mod cache;
 mod dtconv;
+mod error;
+mod estimate;
+mod normalize;
+mod options;
+mod registry;
+mod report;
+mod schema;
+mod sheet_xml;
+mod workbook;
 
-use crate::dtconv::DT_CONV_MAP;
-use calamine::{Reader as XlReader, Xlsx};
+pub use crate::align::{
+    AlignMode, align_lazy_frames, get_aligned_lazy_frames, widen_lazy_frames, widest_common_type,
+};
+pub use crate::arrow::{get_record_batches, get_record_batches_with_options};
+pub use crate::cache::ReadCache;
+pub use crate::dtconv::register_type_alias;
+pub use crate::error::ReadError;
+pub use crate::estimate::estimate_memory;
+pub use crate::options::{
+    BinaryEncoding, ColumnCountPolicy, ColumnTransform, ControlCharSet, ControlChars,
+    DEFAULT_FALSE_TOKENS, DEFAULT_TRUE_TOKENS, DateSystem, Dedup, InvalidValuePolicy, Newlines,
+    ReadOptions, Trim, Unpivot,
+};
+pub use crate::registry::{KindReader, ReaderRegistry};
+pub use crate::report::{ReadReport, ValidationReport};
+pub use crate::workbook::Workbook;
+
+use crate::registry::WithReader;
+use crate::sheet_xml::SheetXml;
+use calamine::{Ods, Reader as XlReader, Xls, Xlsb, Xlsx};
+use either::Either;
 use polars::prelude::{
-    CsvEncoding, DataTypeExpr, Expr, IntoLazy, LazyCsvReader, LazyFileListReader, LazyFrame,
-    NamedFrom, NullValues, PlPath, PlSmallStr, Schema, Series,
+    CsvEncoding, CsvParseOptions, CsvReadOptions, DataTypeExpr, Engine, Expr, IdxSize, IntoLazy,
+    LazyCsvReader, LazyFileListReader, LazyFrame, LazyJsonLineReader, LiteralValue, NULL,
+    NamedFrom, NullValues, PlPath, PlSmallStr, Scalar, ScanArgsIpc, ScanArgsParquet, Schema,
+    SerReader, Series, UnionArgs, UniqueKeepStrategy, UnpivotArgsDSL, cols, concat, len, lit, when,
 };
 use polars_core::prelude::{AnyValue, DataFrame, DataType};
 use qa_settings::Comparable;
+use qa_settings::qa_kind::QaKind;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::fmt;
+use std::fs::File;
+use std::io::{Cursor, Read as _};
 use std::marker::PhantomData;
 use std::path::Path;
-use qa_settings::qa_kind::QaKind;
 
 pub struct Reader<'a, T> {
     inner: Comparable,
+    options: ReadOptions,
     _reader: &'a PhantomData<T>,
 }
 
-pub fn get_lazy_frames(
+pub fn get_lazy_frames(comp: &Comparable) -> Result<(LazyFrame, LazyFrame), ReadError> {
+    get_lazy_frames_with_options(comp, &ReadOptions::default())
+}
+
+pub fn get_lazy_frames_with_options(
     comp: &Comparable,
-) -> Result<(LazyFrame, LazyFrame), Box<dyn std::error::Error>> {
-    match comp.kind() {
-        QaKind::Txt | QaKind::Csv => Reader {
-            inner: comp.clone(),
-            _reader: &PhantomData::<PhantomTxtReader>::default(),
-        }
-        .get_lazy_frames(),
-        QaKind::PivotTable(_) => Reader {
-            inner: comp.clone(),
-            _reader: &PhantomData::<PhantomPivotTableReader>::default(),
+    options: &ReadOptions,
+) -> Result<(LazyFrame, LazyFrame), ReadError> {
+    let ((left, _), (right, _)) = get_lazy_frames_with_report(comp, options)?;
+    Ok((left, right))
+}
+
+/// Reads and collects both sides, for callers that want the data rather
+/// than a query plan.
+pub fn get_data_frames(comp: &Comparable) -> Result<(DataFrame, DataFrame), ReadError> {
+    get_data_frames_with_options(comp, &ReadOptions::default(), Engine::Auto)
+}
+
+/// Reads both sides and collects them with `engine`, such as
+/// [`Engine::Streaming`] for sides larger than memory.
+pub fn get_data_frames_with_options(
+    comp: &Comparable,
+    options: &ReadOptions,
+    engine: Engine,
+) -> Result<(DataFrame, DataFrame), ReadError> {
+    let (left, right) = get_lazy_frames_with_options(comp, options)?;
+    Ok((
+        left.collect_with_engine(engine)?,
+        right.collect_with_engine(engine)?,
+    ))
+}
+
+/// Resolves the schema reads of `comp` apply, without opening its files: the
+/// columns read, with their types, and the columns the schema removes or the
+/// options leave out. Columns left to inference are absent.
+pub fn get_schema(
+    comp: &Comparable,
+    options: &ReadOptions,
+) -> Result<(Schema, Vec<PlSmallStr>), ReadError> {
+    // every reader resolves the schema alike
+    let reader = Reader::<PhantomTxtReader>::new(comp.clone()).with_options(options.clone());
+    let mut schema = Schema::default();
+    let mut removed = Vec::new();
+    for (name, dtype) in reader.schema()? {
+        if dtype == DataType::Null {
+            removed.push(name);
+        } else {
+            schema.insert(name, dtype);
         }
-        .get_lazy_frames(),
-        QaKind::Table(_) => Reader {
-            inner: comp.clone(),
-            _reader: &PhantomData::<PhantomTableReader>::default(),
+    }
+    Ok((schema, removed))
+}
+
+/// Checks that both sides of `comp` can be read, without reading their
+/// rows, as a fast check before a comparison. See
+/// [`Reader::validate_side`] for what is checked.
+pub fn validate(comp: &Comparable, options: &ReadOptions) -> ValidationReport {
+    struct Validate(Side);
+    impl WithReader for Validate {
+        type Output = Vec<ReadError>;
+        fn with<T>(self, reader: &Reader<'static, T>) -> Self::Output
+        where
+            for<'a> &'a Reader<'static, T>: Read,
+        {
+            reader.validate_side(self.0)
         }
-        .get_lazy_frames(),
-        QaKind::SheetRange(_) => Reader {
-            inner: comp.clone(),
-            _reader: &PhantomData::<PhantomSheetRangeReader>::default(),
+    }
+    let side = |side| {
+        registry::with_builtin(comp, options, Validate(side)).unwrap_or_else(|| {
+            vec![
+                format!(
+                    "Reader for kind '{}' is not implemented",
+                    comp.kind().as_str_kind()
+                )
+                .into(),
+            ]
+        })
+    };
+    ValidationReport {
+        left: side(Side::Left),
+        right: side(Side::Right),
+    }
+}
+
+/// Which file of a [`Comparable`] to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// The path of this side's file in `comp`.
+    pub fn path(self, comp: &Comparable) -> &Path {
+        match self {
+            Side::Left => comp.left_path(),
+            Side::Right => comp.right_path(),
         }
-        .get_lazy_frames(),
-        _kind => Err(format!("Reader for kind '{}' is not implemented", _kind.as_str_kind()).into()),
     }
 }
 
+/// Reads one side with the same schema and type handling as
+/// [`get_lazy_frames`], for when there is only one file to load.
+pub fn get_lazy_frame(comp: &Comparable, side: Side) -> Result<LazyFrame, ReadError> {
+    get_lazy_frame_with_options(comp, &ReadOptions::default(), side)
+}
+
+pub fn get_lazy_frame_with_options(
+    comp: &Comparable,
+    options: &ReadOptions,
+    side: Side,
+) -> Result<LazyFrame, ReadError> {
+    let (lf, _) = get_lazy_frame_with_report(comp, options, side)?;
+    Ok(lf)
+}
+
+/// Reads one side along with its [`ReadReport`].
+pub fn get_lazy_frame_with_report(
+    comp: &Comparable,
+    options: &ReadOptions,
+    side: Side,
+) -> Result<(LazyFrame, ReadReport), ReadError> {
+    ReaderRegistry::default().get_lazy_frame_with_report(comp, options, side)
+}
+
+/// Reads both sides along with a [`ReadReport`] for each.
+pub fn get_lazy_frames_with_report(
+    comp: &Comparable,
+    options: &ReadOptions,
+) -> Result<((LazyFrame, ReadReport), (LazyFrame, ReadReport)), ReadError> {
+    ReaderRegistry::default().get_lazy_frames_with_report(comp, options)
+}
+
+/// Runs `read` for both sides as
+/// [`Reader::get_lazy_frames_with_report`] describes.
+pub(crate) fn read_both<R: Send>(
+    read: impl Fn(Side) -> Result<R, ReadError> + Sync,
+) -> Result<(R, R), ReadError> {
+    let (left, right) = std::thread::scope(|scope| {
+        let left = scope.spawn(|| read(Side::Left));
+        let right = read(Side::Right);
+        match left.join() {
+            Ok(left) => (left, right),
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    });
+    Ok((left?, right?))
+}
+
 impl<T> Reader<'_, T> {
     pub fn new(comp: Comparable) -> Self {
         Self {
             inner: comp,
+            options: ReadOptions::default(),
             _reader: &PhantomData,
         }
     }
-    pub fn get_lazy_frames<'a>(
-        &'a self,
-    ) -> Result<(LazyFrame, LazyFrame), Box<dyn std::error::Error>>
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+    pub fn get_lazy_frames<'a>(&'a self) -> Result<(LazyFrame, LazyFrame), ReadError>
     where
         &'a Self: Read,
+        T: Sync,
     {
-        let left = self.read(self.inner.left_path())?;
-        let right = self.read(self.inner.right_path())?;
+        let ((left, _), (right, _)) = self.get_lazy_frames_with_report()?;
         Ok((left, right))
     }
+    /// Finds what would keep one side from being read, without reading its
+    /// rows: invalid options or schema, a missing file, sheet, table or pivot
+    /// table, and a header lacking schema columns, or not matching the
+    /// schema exactly when it is strict.
+    pub fn validate_side<'a>(&'a self, side: Side) -> Vec<ReadError>
+    where
+        &'a Self: Read,
+    {
+        let file = side.path(&self.inner);
+        let mut problems = Vec::new();
+        if let Err(e) = self.options.validate() {
+            problems.push(e);
+        }
+        let schema = self.schema().map_err(|e| problems.push(e)).ok();
+        if !is_url(file)
+            && let Err(e) = std::fs::metadata(file)
+        {
+            problems.push(format!("Cannot read file '{}': {e}", file.display()).into());
+            return problems;
+        }
+        if let Err(e) = self.check_source(file) {
+            problems.push(e);
+            return problems;
+        }
+        let header = match self.header(file) {
+            Ok(header) => header,
+            Err(e) => {
+                problems.push(e);
+                return problems;
+            }
+        };
+        if let (Some(schema), Some(header)) = (schema, header) {
+            let checked = if self.options.strict_schema() {
+                schema::check_strict(&schema, &header)
+            } else {
+                schema::partial(&schema, &header).map(|_| ())
+            };
+            if let Err(e) = checked {
+                problems.push(e);
+            }
+        }
+        problems
+    }
+    /// Reads one side.
+    pub fn get_lazy_frame_with_report<'a>(
+        &'a self,
+        side: Side,
+    ) -> Result<(LazyFrame, ReadReport), ReadError>
+    where
+        &'a Self: Read,
+    {
+        self.read_with_report(side.path(&self.inner))
+    }
+    /// Reads both sides concurrently, the left one on a scoped thread of its
+    /// own. Either side's error is returned, the left one's first.
+    pub fn get_lazy_frames_with_report<'a>(
+        &'a self,
+    ) -> Result<((LazyFrame, ReadReport), (LazyFrame, ReadReport)), ReadError>
+    where
+        &'a Self: Read,
+        T: Sync,
+    {
+        read_both(|side| self.get_lazy_frame_with_report(side))
+    }
+
+    /// Reads a single file, from the cache when one is configured.
+    fn read_with_report<'a>(&'a self, file: &Path) -> Result<(LazyFrame, ReadReport), ReadError>
+    where
+        &'a Self: Read,
+    {
+        // remote files have no modification time to tell a change by, and
+        // transforms are closures, which can't be told apart
+        let Some(cache) = self
+            .options
+            .cache()
+            .filter(|_| !is_url(file) && self.options.transforms().is_empty())
+        else {
+            return self.read_uncached(file);
+        };
+        let settings = format!(
+            "{}{:?}{:?}",
+            self.source_label(file),
+            self.cache_settings(),
+            self.options
+        );
+        let key = cache::ReadCache::key(file, &settings)?;
+        if let Some((df, report)) = cache.get(key) {
+            return Ok((df.lazy(), report));
+        }
+        let (lf, report) = self.read_uncached(file)?;
+        let df = lf.collect()?;
+        cache.insert(key, df.clone(), report.clone());
+        Ok((df.lazy(), report))
+    }
+
+    /// Reads a single file and applies the options shared by every reader.
+    fn read_uncached<'a>(&'a self, file: &Path) -> Result<(LazyFrame, ReadReport), ReadError>
+    where
+        &'a Self: Read,
+    {
+        self.options.validate()?;
+        let mut report = ReadReport::default();
+        for (name, dtype) in self.schema()?.iter() {
+            if dtype != &DataType::Null {
+                continue;
+            }
+            // columns left out of the frame are typed `Null` as well
+            if self.reads_column(name) {
+                report.ignored_columns.push(name.clone());
+            } else {
+                report.unread_columns.push(name.clone());
+            }
+        }
+        // the header is read again apart from the data, only for the checks
+        let checks_header = self.options.strict_schema() || self.options.check_header_names();
+        let header = if checks_header {
+            self.header(file)?
+        } else {
+            None
+        };
+        if let Some(header) = &header {
+            (report.missing_columns, report.extra_columns) =
+                schema::unmatched(&self.schema()?, header);
+        }
+        if self.options.strict_schema() {
+            let header = header.as_ref().ok_or(
+                "A strict schema needs a header row to compare against, but this source has none",
+            )?;
+            schema::check_strict(&self.schema()?, header)?;
+        }
+        if self.options.check_header_names()
+            && let Some(header) = &header
+        {
+            // fails naming the schema columns the header lacks
+            schema::partial(&self.schema()?, header)?;
+        }
+        let lf = self.read(file)?;
+        self.inspect(file, &mut report)?;
+        if !self.options.named_cells().is_empty() {
+            report.named_cells = read_named_cells(
+                self.options.workbook(),
+                file,
+                self.options.named_cells(),
+                self.sheet(file)?,
+            )?;
+        }
+        let lf = match self.options.source_column() {
+            Some(name) if !self.labels_sources() => {
+                lf.with_column(lit(self.source_label(file)).alias(name))
+            }
+            _ => lf,
+        };
+        let lf = self.apply_control_chars(lf, &mut report)?;
+        let lf = self.apply_newlines(lf)?;
+        let lf = self.apply_trim(lf)?;
+        let lf = self.apply_max_string_len(lf, &mut report)?;
+        let lf = self.apply_fixed_lengths(lf)?;
+        let lf = self.apply_transforms(lf);
+        let lf = self.apply_unpivot(lf);
+        let lf = self.apply_null_sentinels(lf, &mut report)?;
+        let lf = self.apply_column_filters(lf)?;
+        let mut lf = self.apply_dedup(lf, &mut report)?;
+        report.columns = lf.collect_schema()?.len();
+        if self.options.count_rows() {
+            report.rows = Some(row_count(&lf)?);
+        }
+        Ok((lf, report))
+    }
+
+    /// Drops duplicate rows keeping the first occurrence, in order.
+    fn apply_dedup(&self, lf: LazyFrame, report: &mut ReadReport) -> Result<LazyFrame, ReadError> {
+        let subset = match self.options.dedup() {
+            Dedup::Off => return Ok(lf),
+            Dedup::Rows => None,
+            Dedup::Keys if self.options.key_columns().is_empty() => {
+                return Err("Deduplicating on keys requires key columns".into());
+            }
+            Dedup::Keys => Some(
+                self.options
+                    .key_columns()
+                    .iter()
+                    .map(|key| Expr::Column(key.as_str().into()))
+                    .collect::<Vec<_>>(),
+            ),
+        };
+        let before = row_count(&lf)?;
+        let lf = lf.unique_stable_generic(subset, UniqueKeepStrategy::First);
+        report.duplicates_removed = Some(before - row_count(&lf)?);
+        Ok(lf)
+    }
+
+    fn apply_column_filters(&self, mut lf: LazyFrame) -> Result<LazyFrame, ReadError> {
+        let include = self.options.include_columns();
+        let exclude = self.options.exclude_columns();
+        if let Some(column) = include
+            .into_iter()
+            .flatten()
+            .find(|column| exclude.contains(column))
+        {
+            return Err(format!("Column '{column}' is both included and excluded").into());
+        }
+        if let Some(include) = include {
+            lf = lf.select(
+                include
+                    .iter()
+                    .map(|column| Expr::Column(column.as_str().into()))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        if !exclude.is_empty() {
+            let schema = lf.collect_schema()?;
+            let keep = schema
+                .iter_names()
+                .filter(|name| !exclude.iter().any(|column| column == name.as_str()))
+                .map(|name| Expr::Column(name.clone()))
+                .collect::<Vec<_>>();
+            lf = lf.select(keep);
+        }
+        Ok(lf)
+    }
+
+    /// Removes or replaces control characters in string columns, counting
+    /// the cells that held any.
+    fn apply_control_chars(
+        &self,
+        mut lf: LazyFrame,
+        report: &mut ReadReport,
+    ) -> Result<LazyFrame, ReadError> {
+        let replacement = match self.options.control_chars() {
+            ControlChars::Keep => return Ok(lf),
+            ControlChars::Remove => String::new(),
+            ControlChars::Replace(c) => c.to_string(),
+        };
+        let pattern = self.options.control_char_set().pattern();
+        let strings = string_columns(&mut lf)?;
+        if strings.is_empty() {
+            report.control_char_cells = Some(0);
+            return Ok(lf);
+        }
+        let counts = lf
+            .clone()
+            .select(
+                strings
+                    .iter()
+                    .map(|name| {
+                        Expr::Column(name.clone())
+                            .str()
+                            .contains(lit(pattern), false)
+                            .sum()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .collect()?;
+        let mut affected = 0;
+        for column in counts.get_columns() {
+            affected += column.get(0)?.extract::<usize>().unwrap_or(0);
+        }
+        report.control_char_cells = Some(affected);
+        Ok(lf.with_columns(
+            strings
+                .iter()
+                .map(|name| {
+                    Expr::Column(name.clone()).str().replace_all(
+                        lit(pattern),
+                        lit(replacement.clone()),
+                        false,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Normalizes or removes line breaks inside string values.
+    fn apply_newlines(&self, mut lf: LazyFrame) -> Result<LazyFrame, ReadError> {
+        let (pattern, replacement) = match self.options.newlines() {
+            Newlines::Keep => return Ok(lf),
+            Newlines::Normalize => (r"\r\n?", "\n"),
+            Newlines::Remove => (r"\r\n|\r|\n", ""),
+        };
+        let strings = string_columns(&mut lf)?;
+        Ok(lf.with_columns(
+            strings
+                .iter()
+                .map(|name| {
+                    Expr::Column(name.clone()).str().replace_all(
+                        lit(pattern),
+                        lit(replacement),
+                        false,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Trims whitespace from the ends of string values the options name.
+    fn apply_trim(&self, lf: LazyFrame) -> Result<LazyFrame, ReadError> {
+        trim_strings(lf, self.options.trim())
+    }
+
+    /// Flags string columns holding values longer than the configured limit,
+    /// truncating them when asked to.
+    fn apply_max_string_len(
+        &self,
+        mut lf: LazyFrame,
+        report: &mut ReadReport,
+    ) -> Result<LazyFrame, ReadError> {
+        let Some(max_len) = self.options.max_string_len() else {
+            return Ok(lf);
+        };
+        let strings = string_columns(&mut lf)?;
+        if strings.is_empty() {
+            return Ok(lf);
+        }
+        let lengths = lf
+            .clone()
+            .select(
+                strings
+                    .iter()
+                    .map(|name| Expr::Column(name.clone()).str().len_chars().max())
+                    .collect::<Vec<_>>(),
+            )
+            .collect()?;
+        for (name, column) in strings.iter().zip(lengths.get_columns()) {
+            if column.get(0)?.extract::<usize>().unwrap_or(0) > max_len {
+                report.long_string_columns.push(name.clone());
+            }
+        }
+        if !self.options.truncate_long_strings() || report.long_string_columns.is_empty() {
+            return Ok(lf);
+        }
+        Ok(lf.with_columns(
+            report
+                .long_string_columns
+                .iter()
+                .map(|name| {
+                    Expr::Column(name.clone())
+                        .str()
+                        .slice(lit(0), lit(max_len as u64))
+                })
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Pads fixed length string columns with spaces and truncates longer
+    /// values, or rejects them when asked to. Columns the frame lacks, left
+    /// out by a partial or dictionary schema, are skipped.
+    fn apply_fixed_lengths<'a>(&'a self, mut lf: LazyFrame) -> Result<LazyFrame, ReadError>
+    where
+        &'a Self: Read,
+    {
+        let lengths = self.fixed_lengths();
+        if lengths.is_empty() {
+            return Ok(lf);
+        }
+        let schema = lf.collect_schema()?;
+        let lengths = lengths
+            .into_iter()
+            .filter(|(name, _)| schema.contains(name))
+            .collect::<Vec<_>>();
+        if lengths.is_empty() {
+            return Ok(lf);
+        }
+        if self.options.reject_overlong_fixed() {
+            let longest = lf
+                .clone()
+                .select(
+                    lengths
+                        .iter()
+                        .map(|(name, _)| Expr::Column(name.clone()).str().len_chars().max())
+                        .collect::<Vec<_>>(),
+                )
+                .collect()?;
+            for ((name, length), column) in lengths.iter().zip(longest.get_columns()) {
+                let found = column.get(0)?.extract::<usize>().unwrap_or(0);
+                if found > *length {
+                    return Err(format!(
+                        "Column '{name}' holds a value of {found} characters, longer than its fixed length of {length}"
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(lf.with_columns(
+            lengths
+                .iter()
+                .map(|(name, length)| {
+                    Expr::Column(name.clone())
+                        .str()
+                        .slice(lit(0), lit(*length as u64))
+                        .str()
+                        .pad_end(lit(*length as u64), ' ')
+                })
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn apply_transforms(&self, mut lf: LazyFrame) -> LazyFrame {
+        for (column, transform) in self.options.transforms() {
+            let name = PlSmallStr::from(column.as_str());
+            lf = lf.with_column(transform(Expr::Column(name.clone())).alias(name));
+        }
+        lf
+    }
+
+    /// Melts wide value columns into variable/value pairs. The source column,
+    /// when requested, stays an id column.
+    fn apply_unpivot(&self, lf: LazyFrame) -> LazyFrame {
+        let Some(unpivot) = self.options.unpivot() else {
+            return lf;
+        };
+        let mut index = unpivot.index.clone();
+        if let Some(source) = self.options.source_column() {
+            index.push(source.to_string());
+        }
+        let variable_name =
+            PlSmallStr::from(unpivot.variable_name.as_deref().unwrap_or("variable"));
+        let lf = lf.unpivot(UnpivotArgsDSL {
+            on: cols(unpivot.on.clone()),
+            index: cols(index),
+            variable_name: Some(variable_name.clone()),
+            value_name: unpivot.value_name.as_deref().map(PlSmallStr::from),
+        });
+        if unpivot.variables.is_empty() {
+            return lf;
+        }
+        let variable = Expr::Column(variable_name.clone());
+        let mut mapped = variable.clone();
+        for (column, value) in unpivot.variables.iter().rev() {
+            mapped = when(variable.clone().eq(lit(column.clone())))
+                .then(lit(value.clone()))
+                .otherwise(mapped);
+        }
+        lf.with_column(mapped.alias(variable_name))
+    }
+
+    fn apply_null_sentinels(
+        &self,
+        mut lf: LazyFrame,
+        report: &mut ReadReport,
+    ) -> Result<LazyFrame, ReadError> {
+        let sentinels = self.options.null_sentinels();
+        if sentinels.is_empty() {
+            return Ok(lf);
+        }
+        let schema = lf.collect_schema()?;
+        let mut fills = Vec::new();
+        for (name, dtype) in schema.iter() {
+            if let Some((_, value)) = sentinels.iter().find(|(dt, _)| dt == dtype) {
+                let sentinel = Expr::Literal(LiteralValue::Scalar(Scalar::new(
+                    value.dtype(),
+                    value.clone(),
+                )))
+                .cast(DataTypeExpr::from(dtype.clone()));
+                fills.push(Expr::Column(name.clone()).fill_null(sentinel));
+                report.null_sentinels.push((name.clone(), value.clone()));
+            }
+        }
+        Ok(lf.with_columns(fills))
+    }
+}
+
+/// The names of a frame's string columns.
+fn string_columns(lf: &mut LazyFrame) -> Result<Vec<PlSmallStr>, ReadError> {
+    Ok(lf
+        .collect_schema()?
+        .iter()
+        .filter(|(_, dtype)| *dtype == &DataType::String)
+        .map(|(name, _)| name.clone())
+        .collect())
+}
+
+/// Trims whitespace from the `trim` ends of a frame's string values.
+fn trim_strings(mut lf: LazyFrame, trim: Trim) -> Result<LazyFrame, ReadError> {
+    if trim == Trim::None {
+        return Ok(lf);
+    }
+    let strings = string_columns(&mut lf)?;
+    Ok(lf.with_columns(
+        strings
+            .iter()
+            .map(|name| {
+                let column = Expr::Column(name.clone()).str();
+                match trim {
+                    Trim::Both | Trim::None => column.strip_chars(lit(NULL)),
+                    Trim::Start => column.strip_chars_start(lit(NULL)),
+                    Trim::End => column.strip_chars_end(lit(NULL)),
+                }
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Counts the rows of a frame without collecting its columns.
+fn row_count(lf: &LazyFrame) -> Result<usize, ReadError> {
+    let df = lf.clone().select([len()]).collect()?;
+    df.get_columns()[0]
+        .get(0)?
+        .extract::<usize>()
+        .ok_or_else(|| "Row count is not a number".into())
 }
 
 struct PhantomTxtReader;
 struct PhantomPivotTableReader;
 struct PhantomTableReader;
 struct PhantomSheetRangeReader;
+struct PhantomDefinedNameReader;
+struct PhantomMultiSheetReader;
+struct PhantomFixedWidthReader;
+
+/// The csv settings a text reader resolves from the `Comparable` and
+/// options. Row counts depend on the file and come from reading it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxtMetadata {
+    pub separator: u8,
+    pub quote_char: Option<u8>,
+    pub eol_char: u8,
+    /// Whether the first line after the skipped ones names the columns. In
+    /// positional mode the header is skipped instead.
+    pub has_header: bool,
+    /// Lines skipped before the header or first record.
+    pub skip_rows: usize,
+}
 
 pub trait Read {
-    type Metadata;
-    fn read(&self, file: &Path) -> Result<LazyFrame, Box<dyn std::error::Error>>;
-    fn schema(&self) -> Result<Schema, Box<dyn std::error::Error>> {
+    type Metadata: fmt::Debug;
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError>;
+    fn schema(&self) -> Result<Schema, ReadError> {
         let raw = self.raw_schema();
         if raw.is_empty() {
-            return Err("Read failed due to empty provided schema".into());
+            // every column is inferred
+            if self.options().infer_schema().is_some() {
+                return Ok(Schema::default());
+            }
+            return Err(ReadError::EmptySchema);
+        }
+        let mut duplicates = Vec::new();
+        for (index, (col_name, _)) in raw.iter().enumerate() {
+            if raw[..index].iter().any(|(name, _)| name == col_name)
+                && !duplicates.contains(col_name)
+            {
+                duplicates.push(col_name.clone());
+            }
+        }
+        if !duplicates.is_empty() {
+            return Err(ReadError::DuplicateColumns(duplicates));
         }
         let mut schema = Schema::default();
         for (col_name, type_str) in raw.into_iter() {
-            schema.insert(
-                col_name.to_string().into(),
-                DT_CONV_MAP.get(type_str).unwrap().clone(),
-            );
+            let (type_str, length) = dtconv::split_length(type_str);
+            let dtype = dtconv::parse_type(type_str).ok_or_else(|| ReadError::UnknownType {
+                column: col_name.to_string(),
+                type_name: type_str.to_string(),
+            })?;
+            if length.is_some() && dtype != DataType::String {
+                return Err(format!(
+                    "Column '{col_name}' declares a length but only string columns can have one"
+                )
+                .into());
+            }
+            // columns left out of the frame are never read
+            let dtype = if self.reads_column(col_name) {
+                dtype
+            } else {
+                DataType::Null
+            };
+            schema.insert(col_name.to_string().into(), dtype);
+        }
+        Ok(schema)
+    }
+    /// Whether the column `name` is read, rather than left out by
+    /// [`ReadOptions::with_include_columns`]. Unpivoting needs every column.
+    fn reads_column(&self, name: &str) -> bool {
+        match self.options().include_columns() {
+            Some(include) if self.options().unpivot().is_none() => {
+                include.iter().any(|column| column == name)
+            }
+            _ => true,
+        }
+    }
+    /// String columns declared with a fixed length, as in `str(10)`.
+    fn fixed_lengths(&self) -> Vec<(PlSmallStr, usize)> {
+        self.raw_schema()
+            .iter()
+            .filter(|(col_name, _)| self.reads_column(col_name))
+            .filter_map(|(col_name, type_str)| {
+                let (_, length) = dtconv::split_length(type_str);
+                length.map(|length| (PlSmallStr::from(col_name.as_str()), length))
+            })
+            .collect()
+    }
+    /// Records reader specific findings about `file` in the report.
+    fn inspect(&self, _file: &Path, _report: &mut ReadReport) -> Result<(), ReadError> {
+        Ok(())
+    }
+    /// Describes the settings the read depends on beyond the options, to
+    /// tell cached reads of the same file apart.
+    fn cache_settings(&self) -> String {
+        format!("{:?}{:?}", self.raw_schema(), self.metadata())
+    }
+    /// The column names in `file`, when it carries any.
+    fn header(&self, _file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        Ok(None)
+    }
+    /// Checks that the sheet, table or pivot table read from `file` exists,
+    /// reading as little of it as the format allows.
+    fn check_source(&self, file: &Path) -> Result<(), ReadError> {
+        self.header(file).map(|_| ())
+    }
+    /// The worksheet the rows are read from, for cell references that don't
+    /// name one.
+    fn sheet(&self, _file: &Path) -> Result<Option<String>, ReadError> {
+        Ok(None)
+    }
+    /// Identifies where the rows read from `file` came from.
+    fn source_label(&self, file: &Path) -> String {
+        file.display().to_string()
+    }
+    /// Whether [`Read::read`] adds the source column itself, for readers
+    /// whose rows come from several sources.
+    fn labels_sources(&self) -> bool {
+        false
+    }
+    fn metadata(&self) -> Self::Metadata;
+    fn raw_schema(&self) -> &[(String, String)];
+    fn options(&self) -> &ReadOptions;
+}
+
+impl Reader<'_, PhantomTxtReader> {
+    /// In positional mode the header line, if any, is skipped as data would
+    /// be and the schema names are assigned by position.
+    fn csv_has_header(&self) -> bool {
+        self.inner.has_header() && !self.options.positional_columns()
+    }
+
+    fn csv_skip_rows(&self) -> usize {
+        if self.inner.has_header() && self.options.positional_columns() {
+            self.inner.skip_lines() + 1
+        } else {
+            self.inner.skip_lines()
+        }
+    }
+
+    /// Rejects settings polars would silently misparse with: the separator,
+    /// quote and end of line characters must all differ.
+    fn check_csv_config(&self) -> Result<(), ReadError> {
+        let separator = self.inner.separator();
+        let eol = self.inner.eol_char();
+        let describe = |c: u8| format!("{:?}", c as char);
+        if separator == eol {
+            return Err(ReadError::InvalidCsvConfig(format!(
+                "separator and end of line are both {}",
+                describe(separator)
+            )));
+        }
+        if let Some(quote) = self.inner.quote_char() {
+            if quote == separator {
+                return Err(ReadError::InvalidCsvConfig(format!(
+                    "separator and quote are both {}",
+                    describe(quote)
+                )));
+            }
+            if quote == eol {
+                return Err(ReadError::InvalidCsvConfig(format!(
+                    "quote and end of line are both {}",
+                    describe(quote)
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no record of csv content is longer than the configured
+    /// limit. Line breaks inside quotes don't end a record.
+    fn check_line_lengths(&self, mut reader: impl std::io::BufRead) -> Result<(), ReadError> {
+        let Some(limit) = self.options.max_line_bytes() else {
+            return Ok(());
+        };
+        let eol = self.inner.eol_char();
+        let quote = self.inner.quote_char();
+        let mut line = 1;
+        let mut length: u64 = 0;
+        let mut quoted = false;
+        loop {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                return Ok(());
+            }
+            for &byte in buffer {
+                if Some(byte) == quote {
+                    quoted = !quoted;
+                }
+                if byte == eol && !quoted {
+                    line += 1;
+                    length = 0;
+                    continue;
+                }
+                length += 1;
+                if length > limit {
+                    return Err(ReadError::LineTooLong { line, limit });
+                }
+            }
+            let consumed = buffer.len();
+            reader.consume(consumed);
+        }
+    }
+
+    /// A csv reader configured from the `Comparable`, without a schema.
+    fn csv_reader(&self, file: &Path) -> Result<LazyCsvReader, ReadError> {
+        Ok(
+            LazyCsvReader::new(PlPath::from_str(file.to_str().ok_or("Invalid file path")?))
+                .with_has_header(self.csv_has_header())
+                .with_separator(self.inner.separator())
+                .with_rechunk(self.inner.rechunk())
+                .with_eol_char(self.inner.eol_char())
+                .with_ignore_errors(self.inner.ignore_errors())
+                .with_null_values(self.csv_null_values())
+                .with_quote_char(self.inner.quote_char())
+                .with_low_memory(self.inner.low_memory())
+                .with_encoding(if self.inner.enforce_utf8() {
+                    CsvEncoding::Utf8
+                } else {
+                    CsvEncoding::LossyUtf8
+                })
+                .with_skip_rows(self.csv_skip_rows())
+                .with_n_rows(self.options.n_rows())
+                .with_missing_is_null(self.inner.missing_is_null()),
+        )
+    }
+
+    /// The `Comparable`'s null tokens, for every column. The tokens of single
+    /// columns are nulled once read, as the csv reader takes one per column.
+    fn csv_null_values(&self) -> Option<NullValues> {
+        self.inner
+            .null_values()
+            .map(|v| NullValues::AllColumns(v.iter().map(|v| PlSmallStr::from(v)).collect()))
+    }
+
+    /// The eager equivalent of [`Self::csv_reader`], for small files where
+    /// building and optimizing a lazy plan dominates the read.
+    fn csv_read_options(&self) -> CsvReadOptions {
+        CsvReadOptions::default()
+            .with_has_header(self.csv_has_header())
+            .with_rechunk(self.inner.rechunk())
+            .with_ignore_errors(self.inner.ignore_errors())
+            .with_low_memory(self.inner.low_memory())
+            .with_skip_rows(self.csv_skip_rows())
+            .with_n_rows(self.options.n_rows())
+            .with_parse_options(
+                CsvParseOptions::default()
+                    .with_separator(self.inner.separator())
+                    .with_eol_char(self.inner.eol_char())
+                    .with_null_values(self.csv_null_values())
+                    .with_quote_char(self.inner.quote_char())
+                    .with_encoding(if self.inner.enforce_utf8() {
+                        CsvEncoding::Utf8
+                    } else {
+                        CsvEncoding::LossyUtf8
+                    })
+                    .with_missing_is_null(self.inner.missing_is_null()),
+            )
+    }
+
+    /// The column names of the file's header line.
+    fn csv_header(&self, file: &Path) -> Result<Vec<PlSmallStr>, ReadError> {
+        self.check_csv_config()?;
+        if !self.csv_has_header() {
+            return Err("Reading column names requires a file with a header".into());
+        }
+        if self.encoding()?.is_some() {
+            return self.bytes_header(&self.decode(std::fs::read(file)?)?);
+        }
+        let mut lf = self
+            .csv_reader(file)?
+            .with_infer_schema_length(Some(0))
+            .finish()?;
+        Ok(lf.collect_schema()?.iter_names().cloned().collect())
+    }
+
+    /// The column names of the header line of csv `bytes`.
+    fn bytes_header(&self, bytes: &[u8]) -> Result<Vec<PlSmallStr>, ReadError> {
+        let header = self
+            .csv_read_options()
+            .with_n_rows(Some(0))
+            .with_infer_schema_length(Some(0))
+            .into_reader_with_file_handle(Cursor::new(bytes))
+            .finish()?;
+        Ok(header.get_column_names_owned())
+    }
+
+    /// The encoding set by [`ReadOptions::with_encoding`].
+    fn encoding(&self) -> Result<Option<&'static encoding_rs::Encoding>, ReadError> {
+        self.options
+            .encoding()
+            .map(|label| {
+                encoding_rs::Encoding::for_label(label.as_bytes())
+                    .ok_or_else(|| format!("Unknown encoding '{label}'").into())
+            })
+            .transpose()
+    }
+
+    /// Transcodes `bytes` to UTF-8 from the configured encoding, if any. A
+    /// byte order mark overrides the encoding.
+    fn decode(&self, bytes: Vec<u8>) -> Result<Vec<u8>, ReadError> {
+        let Some(encoding) = self.encoding()? else {
+            return Ok(bytes);
+        };
+        let (text, _, _) = encoding.decode(&bytes);
+        Ok(text.into_owned().into_bytes())
+    }
+
+    /// Downloads a csv file served over http(s), within the configured
+    /// timeout.
+    fn download(&self, url: &Path) -> Result<Vec<u8>, ReadError> {
+        let url = url.to_str().ok_or("Invalid url")?;
+        let agent = ureq::AgentBuilder::new()
+            .timeout(self.options.http_timeout())
+            .build();
+        let mut bytes = Vec::new();
+        agent
+            .get(url)
+            .call()
+            .map_err(|e| format!("Cannot fetch '{url}': {e}"))?
+            .into_reader()
+            .read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Extracts the csv member named by [`ReadOptions::with_zip_member`]
+    /// from a zip archive, or its only member when none is named.
+    fn zip_member(&self, file: &Path) -> Result<Vec<u8>, ReadError> {
+        let mut archive = zip::ZipArchive::new(File::open(file)?)?;
+        let name = match self.options.zip_member() {
+            Some(name) => name.to_string(),
+            None if archive.len() == 1 => archive.name_for_index(0).unwrap_or_default().to_string(),
+            None => {
+                return Err(format!(
+                    "'{}' holds {} files, name the one to read with a zip member",
+                    file.display(),
+                    archive.len()
+                )
+                .into());
+            }
+        };
+        let is_csv = Path::new(&name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| matches!(e.as_str(), "csv" | "txt" | "tsv"));
+        if !is_csv {
+            return Err(format!("Zip member '{name}' is not a csv or text file").into());
+        }
+        let mut member = archive.by_name(&name).map_err(|e| match e {
+            zip::result::ZipError::FileNotFound => {
+                ReadError::Invalid(format!("'{}' has no member '{name}'", file.display()))
+            }
+            e => ReadError::Zip(e),
+        })?;
+        let mut bytes = Vec::with_capacity(member.size() as usize);
+        member.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// The rows of the `<table>` of an html page chosen by
+    /// [`ReadOptions::with_html_table`], the first by default. A cell
+    /// spanning several columns or rows repeats its text in each of them.
+    fn html_rows(&self, file: &Path) -> Result<Vec<Vec<calamine::Data>>, ReadError> {
+        let page = scraper::Html::parse_document(&std::fs::read_to_string(file)?);
+        let index = self.options.html_table();
+        let selector = |css: &str| {
+            scraper::Selector::parse(css).map_err(|e| ReadError::Invalid(e.to_string()))
+        };
+        let table = page
+            .select(&selector("table")?)
+            .nth(index)
+            .ok_or_else(|| format!("'{}' has no table {index}", file.display()))?;
+        let row_selector = selector("tr")?;
+        let cell_selector = selector("th, td")?;
+        let mut rows: Vec<Vec<calamine::Data>> = Vec::new();
+        // cells spanning down from earlier rows, by column: text and rows left
+        let mut spans: Vec<Option<(calamine::Data, usize)>> = Vec::new();
+        for tr in table.select(&row_selector) {
+            let mut row = Vec::new();
+            let mut cells = tr.select(&cell_selector);
+            loop {
+                let column = row.len();
+                if let Some(Some((value, left))) = spans.get_mut(column) {
+                    row.push(value.clone());
+                    *left -= 1;
+                    if *left == 0 {
+                        spans[column] = None;
+                    }
+                    continue;
+                }
+                let Some(cell) = cells.next() else {
+                    break;
+                };
+                let text = cell.text().collect::<String>();
+                let value = match text.trim() {
+                    "" => calamine::Data::Empty,
+                    text => calamine::Data::String(text.to_string()),
+                };
+                let span = |name: &str| {
+                    cell.value()
+                        .attr(name)
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .unwrap_or(1)
+                        .max(1)
+                };
+                for _ in 0..span("colspan") {
+                    let column = row.len();
+                    if spans.len() <= column {
+                        spans.resize(column + 1, None);
+                    }
+                    if span("rowspan") > 1 {
+                        spans[column] = Some((value.clone(), span("rowspan") - 1));
+                    }
+                    row.push(value.clone());
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Reads the html table chosen by [`Self::html_rows`].
+    fn read_html(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        let mut schema = self.schema()?;
+        let mut rows = self
+            .html_rows(file)?
+            .into_iter()
+            .skip(self.inner.skip_lines());
+        if self.inner.has_header() {
+            let header = rows.next().unwrap_or_default();
+            if self.options.schema_dictionary() {
+                schema = schema::from_dictionary(&schema, &header_names(&header))?;
+            } else if self.options.infer_schema().is_some() {
+                schema = schema::partial(&schema, &header_names(&header))?;
+            }
+        }
+        let first_row = self.inner.skip_lines() + usize::from(self.inner.has_header());
+        let origin = CellOrigin::new(file.display().to_string(), (first_row as u32, 0));
+        Ok(build_frame(
+            rows.map(Ok::<_, Infallible>),
+            &schema,
+            &self.options,
+            &origin,
+        )?
+        .lazy())
+    }
+
+    /// Reads csv content already in memory, such as a decompressed file,
+    /// with the same settings as a csv file on disk.
+    fn read_csv_bytes(&self, bytes: Vec<u8>) -> Result<LazyFrame, ReadError> {
+        self.check_line_lengths(&bytes[..])?;
+        let bytes = self.decode(bytes)?;
+        let mut schema = self.schema()?;
+        if self.options.schema_dictionary() {
+            if !self.csv_has_header() {
+                return Err("Reading column names requires a file with a header".into());
+            }
+            schema = schema::from_dictionary(&schema, &self.bytes_header(&bytes)?)?;
+        }
+        if self.options.transpose() {
+            return self.read_transposed_bytes(bytes, &schema);
+        }
+        if self.options.infer_schema().is_some() {
+            let df = self
+                .csv_read_options()
+                .with_infer_schema_length(self.options.infer_schema())
+                .with_schema_overwrite(Some(
+                    normalize::overwrite_schema(&schema, &self.options).into(),
+                ))
+                .into_reader_with_file_handle(Cursor::new(bytes))
+                .finish()?;
+            return self.select_partial(df.lazy(), &schema);
+        }
+        let ignore_columns = normalize::select_columns(&schema, &self.options);
+        let schema = normalize::text_schema(&schema, &self.options);
+        let df = self
+            .csv_read_options()
+            .with_schema(Some(schema.into()))
+            .into_reader_with_file_handle(Cursor::new(bytes))
+            .finish()?;
+        Ok(df.lazy().select(ignore_columns))
+    }
+
+    /// Selects the columns of a csv frame read with inference, casting the
+    /// declared ones.
+    fn select_partial(&self, mut lf: LazyFrame, schema: &Schema) -> Result<LazyFrame, ReadError> {
+        let file = lf.collect_schema()?;
+        check_declared(schema, &file)?;
+        Ok(lf.select(normalize::select_partial(&file, schema, &self.options)))
+    }
+
+    /// Scans a Parquet, Arrow IPC or ndjson file as its extension tells, with
+    /// the types it carries. `None` for other files.
+    fn scan_typed(&self, file: &Path) -> Result<Option<LazyFrame>, ReadError> {
+        Ok(Some(match extension(file).as_str() {
+            "parquet" => self.scan_parquet(file)?,
+            "ndjson" | "jsonl" => self.scan_ndjson(file)?,
+            "arrow" | "ipc" | "feather" => self.scan_ipc(file)?,
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Scans a Parquet file lazily.
+    fn scan_parquet(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        check_not_empty(file)?;
+        let args = ScanArgsParquet {
+            low_memory: self.inner.low_memory(),
+            rechunk: self.inner.rechunk(),
+            ..Default::default()
+        };
+        Ok(LazyFrame::scan_parquet(
+            PlPath::from_str(file.to_str().ok_or("Invalid file path")?),
+            args,
+        )?)
+    }
+
+    /// Scans an Arrow IPC (Feather v2) file lazily.
+    fn scan_ipc(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        check_not_empty(file)?;
+        let args = ScanArgsIpc {
+            rechunk: self.inner.rechunk(),
+            ..Default::default()
+        };
+        Ok(LazyFrame::scan_ipc(
+            PlPath::from_str(file.to_str().ok_or("Invalid file path")?),
+            args,
+        )?)
+    }
+
+    /// Reads newline-delimited json. Nested objects become `Struct` columns
+    /// and are not flattened, so the schema names top-level fields only.
+    fn scan_ndjson(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        check_not_empty(file)?;
+        Ok(
+            LazyJsonLineReader::new(PlPath::from_str(file.to_str().ok_or("Invalid file path")?))
+                .with_low_memory(self.inner.low_memory())
+                .with_rechunk(self.inner.rechunk())
+                .finish()?,
+        )
+    }
+
+    /// The csv content of a remote, zip or gzip file, fetched or extracted.
+    /// `None` for csv files read in place.
+    fn csv_bytes(&self, file: &Path) -> Result<Option<Vec<u8>>, ReadError> {
+        if is_url(file) {
+            return Ok(Some(self.download(file)?));
+        }
+        match extension(file).as_str() {
+            "zip" => Ok(Some(self.zip_member(file)?)),
+            "gz" => Ok(Some(gunzip(File::open(file)?)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Applies the schema to a frame read from a typed format, as a cast so
+    /// compatible source types still line up.
+    fn cast_typed(&self, lf: LazyFrame) -> Result<LazyFrame, ReadError> {
+        // pushed down into the scan
+        let mut lf = match (self.options.row_offset(), self.options.n_rows()) {
+            (0, None) => lf,
+            (0, Some(n_rows)) => lf.limit(n_rows as IdxSize),
+            (offset, n_rows) => lf.slice(
+                offset as i64,
+                n_rows.map_or(IdxSize::MAX, |n_rows| n_rows as IdxSize),
+            ),
+        };
+        let mut schema = self.schema()?;
+        // typed formats carry the types of the undeclared columns
+        if self.options.infer_schema().is_some() {
+            let file = lf.collect_schema()?;
+            check_declared(&schema, &file)?;
+            return Ok(lf.select(
+                file.iter_names()
+                    .filter_map(|name| match schema.get(name) {
+                        None => Some(Expr::Column(name.clone())),
+                        Some(DataType::Null) => None,
+                        Some(dtype) => Some(normalize::cast_column(name, dtype, &self.options)),
+                    })
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        if self.options.schema_dictionary() {
+            let names = lf
+                .collect_schema()?
+                .iter_names()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>();
+            schema = schema::from_dictionary(&schema, &names)?;
+        }
+        Ok(lf.select(normalize::cast_columns(&schema, &self.options)))
+    }
+
+    /// Reads every cell as a string, transposes the file so each line becomes
+    /// a column named after the schema, then casts to the schema types.
+    fn read_transposed(&self, file: &Path, schema: &Schema) -> Result<LazyFrame, ReadError> {
+        // infer nothing so every column is read as a string
+        // every line is a column, the row limit applies once transposed
+        let df = self
+            .csv_reader(file)?
+            .with_infer_schema_length(Some(0))
+            .with_n_rows(None)
+            .finish()?
+            .collect()?;
+        self.transpose_lines(df, schema)
+    }
+
+    /// The in-memory equivalent of [`Self::read_transposed`].
+    fn read_transposed_bytes(
+        &self,
+        bytes: Vec<u8>,
+        schema: &Schema,
+    ) -> Result<LazyFrame, ReadError> {
+        let df = self
+            .csv_read_options()
+            .with_infer_schema_length(Some(0))
+            .with_n_rows(None)
+            .into_reader_with_file_handle(Cursor::new(bytes))
+            .finish()?;
+        self.transpose_lines(df, schema)
+    }
+
+    /// Turns the lines of a file read as strings into the schema columns.
+    fn transpose_lines(&self, mut df: DataFrame, schema: &Schema) -> Result<LazyFrame, ReadError> {
+        if df.height() != schema.len() {
+            return Err(format!(
+                "Transposed file has {} lines but the schema declares {} columns",
+                df.height(),
+                schema.len()
+            )
+            .into());
+        }
+        let names = schema.iter_names().map(|n| n.to_string()).collect();
+        let mut df = df.transpose(None, Some(Either::Right(names)))?;
+        if let Some(n_rows) = self.options.n_rows() {
+            df = df.head(Some(n_rows));
+        }
+        Ok(df.lazy().select(
+            schema
+                .iter()
+                .filter(|(_, dt)| *dt != &DataType::Null)
+                .map(|(name, dt)| normalize::from_text(name, dt, &self.options))
+                .collect::<Vec<_>>(),
+        ))
+    }
+}
+
+/// Reads a text or csv file, local or at an http(s) url. Other formats are
+/// chosen by extension:
+///
+/// - `.gz`: gzip compressed csv;
+/// - `.zip`: a csv member of a zip archive;
+/// - `.parquet`: Parquet;
+/// - `.arrow`, `.ipc`, `.feather`: Arrow IPC;
+/// - `.ndjson`, `.jsonl`: newline-delimited json;
+/// - `.html`, `.htm`: a table of an html page.
+///
+/// Fields are quoted with `Comparable::quote_char()`, which may be any single
+/// byte such as `'`. Inside a quoted field the separator and line endings are
+/// literal, and the quote itself is escaped by doubling it (`'it''s'`).
+/// Backslash escapes (`\'`) are not understood: the backslash is kept and the
+/// quote ends the field. A quote char of `None` disables quoting.
+impl Read for &'_ Reader<'_, PhantomTxtReader> {
+    type Metadata = TxtMetadata;
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        if let Some(lf) = self.scan_typed(file)? {
+            return self.cast_typed(lf);
+        }
+        if matches!(extension(file).as_str(), "html" | "htm") {
+            return self.read_html(file);
+        }
+        self.check_csv_config()?;
+        if let Some(bytes) = self.csv_bytes(file)? {
+            return self.read_csv_bytes(bytes);
+        }
+        if self.encoding()?.is_some() {
+            return self.read_csv_bytes(std::fs::read(file)?);
+        }
+        self.check_line_lengths(std::io::BufReader::new(File::open(file)?))?;
+        let mut schema = self.schema()?;
+        if self.options.schema_dictionary() {
+            schema = schema::from_dictionary(&schema, &self.csv_header(file)?)?;
+        }
+        if self.options.transpose() {
+            return self.read_transposed(file, &schema);
+        }
+        if self.options.infer_schema().is_some() {
+            let lf = self
+                .csv_reader(file)?
+                .with_infer_schema_length(self.options.infer_schema())
+                .with_schema_overwrite(Some(
+                    normalize::overwrite_schema(&schema, &self.options).into(),
+                ))
+                .finish()?;
+            return self.select_partial(lf, &schema);
+        }
+        let ignore_columns = normalize::select_columns(&schema, &self.options);
+        let schema = normalize::text_schema(&schema, &self.options);
+        if let Some(threshold) = self.options.eager_threshold()
+            && std::fs::metadata(file)?.len() < threshold
+        {
+            let df = self
+                .csv_read_options()
+                .with_schema(Some(schema.into()))
+                .try_into_reader_with_file_path(Some(file.to_path_buf()))?
+                .finish()?;
+            return Ok(df.lazy().select(ignore_columns));
+        }
+        Ok(self
+            .csv_reader(file)?
+            .with_schema(Some(schema.into()))
+            .finish()?
+            .select(ignore_columns))
+    }
+
+    fn cache_settings(&self) -> String {
+        format!(
+            "{:?}{}{}{:?}{}{}{:?}{}{}{}",
+            self.raw_schema(),
+            self.inner.separator(),
+            self.inner.eol_char(),
+            self.inner.quote_char(),
+            self.inner.has_header(),
+            self.inner.skip_lines(),
+            self.inner.null_values(),
+            self.inner.ignore_errors(),
+            self.inner.missing_is_null(),
+            self.inner.enforce_utf8()
+        )
+    }
+
+    /// The column names of typed formats, of the html table's header row
+    /// and of the csv header line, for whichever the extension selects.
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        if let Some(mut lf) = self.scan_typed(file)? {
+            let schema = lf.collect_schema()?;
+            return Ok(Some(schema.iter_names().map(|n| n.to_string()).collect()));
+        }
+        if matches!(extension(file).as_str(), "html" | "htm") {
+            if !self.inner.has_header() {
+                return Ok(None);
+            }
+            let rows = self.html_rows(file)?;
+            let header = rows.get(self.inner.skip_lines()).map(Vec::as_slice);
+            return Ok(Some(header_names(header.unwrap_or_default())));
+        }
+        if !self.csv_has_header() || self.options.transpose() {
+            return Ok(None);
+        }
+        let header = match self.csv_bytes(file)? {
+            Some(bytes) => {
+                self.check_csv_config()?;
+                self.bytes_header(&self.decode(bytes)?)?
+            }
+            None => self.csv_header(file)?,
+        };
+        Ok(Some(header.iter().map(|name| name.to_string()).collect()))
+    }
+
+    /// Records the slice of a typed file read, and counts the invalid UTF-8
+    /// sequences a lossy read replaces, so files in another encoding can be
+    /// told apart. Remote files are not counted,
+    /// which would take a second download.
+    fn inspect(&self, file: &Path, report: &mut ReadReport) -> Result<(), ReadError> {
+        let typed = matches!(
+            extension(file).as_str(),
+            "parquet" | "ndjson" | "jsonl" | "arrow" | "ipc" | "feather"
+        );
+        if typed && (self.options.row_offset() > 0 || self.options.n_rows().is_some()) {
+            report.row_slice = Some((self.options.row_offset(), self.options.n_rows()));
+        }
+        if self.inner.enforce_utf8() || self.options.encoding().is_some() || is_url(file) {
+            return Ok(());
+        }
+        report.invalid_utf8 = match extension(file).as_str() {
+            "html" | "htm" => return Ok(()),
+            _ if typed => return Ok(()),
+            "zip" => Some(count_invalid_utf8(&self.zip_member(file)?[..])?),
+            "gz" => Some(count_invalid_utf8(flate2::read::MultiGzDecoder::new(
+                File::open(file)?,
+            ))?),
+            _ => Some(count_invalid_utf8(File::open(file)?)?),
+        };
+        Ok(())
+    }
+
+    fn metadata(&self) -> Self::Metadata {
+        TxtMetadata {
+            separator: self.inner.separator(),
+            quote_char: self.inner.quote_char(),
+            eol_char: self.inner.eol_char(),
+            has_header: self.csv_has_header(),
+            skip_rows: self.csv_skip_rows(),
+        }
+    }
+
+    fn raw_schema(&self) -> &[(String, String)] {
+        self.inner.schema()
+    }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+}
+
+/// Builds a frame from rows of Excel cells, assigning cells to schema columns
+/// in order. Columns typed `Null` are skipped. When transposing, each source
+/// column becomes a row before assignment.
+fn build_frame<I, R, E>(
+    rows: I,
+    schema: &Schema,
+    options: &ReadOptions,
+    origin: &CellOrigin,
+) -> Result<DataFrame, ReadError>
+where
+    I: IntoIterator<Item = Result<R, E>>,
+    R: AsRef<[calamine::Data]>,
+    E: Into<ReadError>,
+{
+    let inferring = schema.is_empty()
+        || schema
+            .iter_values()
+            .any(|dtype| matches!(dtype, DataType::Unknown(_)));
+    // stops pulling rows, and streamed cells, once there are enough
+    let n_rows = options.n_rows().unwrap_or(usize::MAX);
+    if !options.transpose() && !inferring {
+        return columns_to_frame(rows.into_iter().take(n_rows), schema, options, origin);
+    }
+    // transposed rows are source columns, so every source row is needed
+    let take = if options.transpose() {
+        usize::MAX
+    } else {
+        n_rows
+    };
+    let mut rows = rows
+        .into_iter()
+        .take(take)
+        .map(|row| row.map(|r| r.as_ref().to_vec()).map_err(Into::into))
+        .collect::<Result<Vec<_>, ReadError>>()?;
+    if options.transpose() {
+        rows = transpose_rows(&rows);
+        rows.truncate(n_rows);
+    }
+    if inferring {
+        let schema = infer_schema(schema, &rows, options.infer_schema().unwrap_or(100));
+        return columns_to_frame(
+            rows.into_iter().map(Ok::<_, Infallible>),
+            &schema,
+            options,
+            origin,
+        );
+    }
+    columns_to_frame(
+        rows.into_iter().map(Ok::<_, Infallible>),
+        schema,
+        options,
+        origin,
+    )
+}
+
+/// Types the columns left to inference, typed `Unknown`, from the first
+/// `sample` rows. An empty schema stands for every column, named by
+/// position as polars names headerless csv columns.
+fn infer_schema(schema: &Schema, rows: &[Vec<calamine::Data>], sample: usize) -> Schema {
+    let sampled = &rows[..sample.min(rows.len())];
+    let infer = |column: usize| dtconv::infer_type(sampled.iter().filter_map(|r| r.get(column)));
+    if schema.is_empty() {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        return (0..width)
+            .map(|column| {
+                (
+                    PlSmallStr::from(format!("column_{}", column + 1)),
+                    infer(column),
+                )
+            })
+            .collect();
+    }
+    schema
+        .iter()
+        .enumerate()
+        .map(|(column, (name, dtype))| match dtype {
+            DataType::Unknown(_) => (name.clone(), infer(column)),
+            _ => (name.clone(), dtype.clone()),
+        })
+        .collect()
+}
+
+/// Counts the invalid UTF-8 sequences in `reader`, each of which a lossy
+/// decode replaces with one U+FFFD character.
+fn count_invalid_utf8(mut reader: impl std::io::Read) -> Result<usize, ReadError> {
+    let mut buffer = vec![0; 64 * 1024];
+    // bytes of a sequence cut off by the end of the last chunk
+    let mut pending = Vec::new();
+    let mut count = 0;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(count + usize::from(!pending.is_empty()));
+        }
+        pending.extend_from_slice(&buffer[..read]);
+        let mut rest = &pending[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(_) => rest = &[],
+                Err(e) => match e.error_len() {
+                    Some(len) => {
+                        count += 1;
+                        rest = &rest[e.valid_up_to() + len..];
+                        continue;
+                    }
+                    None => rest = &rest[e.valid_up_to()..],
+                },
+            }
+            break;
+        }
+        pending = rest.to_vec();
+    }
+}
+
+/// Whether `file` is an http(s) url rather than a local path.
+fn is_url(file: &Path) -> bool {
+    file.to_str()
+        .is_some_and(|f| f.starts_with("http://") || f.starts_with("https://"))
+}
+
+/// The lowercase extension of `file`, which decides the workbook format.
+fn extension(file: &Path) -> String {
+    file.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Reads the used range of `sheet`, from `workbook` when it is `file`.
+/// Otherwise opens `file` in the format its extension names, and as an xlsx
+/// workbook for any other extension.
+fn worksheet_range(
+    workbook: Option<&Workbook>,
+    file: &Path,
+    sheet: &str,
+) -> Result<calamine::Range<calamine::Data>, ReadError> {
+    if let Some(wb) = workbook.filter(|wb| wb.path() == file) {
+        return wb.with(|wb| sheet_range(wb.xlsx(), sheet));
+    }
+    match extension(file).as_str() {
+        "ods" => sheet_range(&mut calamine::open_workbook::<Ods<_>, _>(file)?, sheet),
+        "xls" => sheet_range(&mut calamine::open_workbook::<Xls<_>, _>(file)?, sheet),
+        "xlsb" => sheet_range(&mut calamine::open_workbook::<Xlsb<_>, _>(file)?, sheet),
+        _ => sheet_range(&mut calamine::open_workbook::<Xlsx<_>, _>(file)?, sheet),
+    }
+}
+
+/// The used range of `sheet` in `wb`, failing with the sheet's name when the
+/// workbook has no such sheet.
+fn sheet_range<RS, R>(wb: &mut R, sheet: &str) -> Result<calamine::Range<calamine::Data>, ReadError>
+where
+    RS: std::io::Read + std::io::Seek,
+    R: XlReader<RS>,
+    R::Error: Into<ReadError>,
+{
+    if !wb.sheet_names().iter().any(|name| name == sheet) {
+        return Err(ReadError::NotFound {
+            what: "sheet",
+            name: sheet.to_string(),
+        });
+    }
+    wb.worksheet_range(sheet).map_err(Into::into)
+}
+
+/// The table `name` of a workbook whose tables are loaded, failing with the
+/// name when there is no such table.
+fn table_by_name<RS>(
+    wb: &mut Xlsx<RS>,
+    name: &str,
+) -> Result<calamine::Table<calamine::Data>, ReadError>
+where
+    RS: std::io::Read + std::io::Seek,
+{
+    if !wb.table_names().iter().any(|table| table.as_str() == name) {
+        return Err(ReadError::NotFound {
+            what: "table",
+            name: name.to_string(),
+        });
+    }
+    Ok(wb.table_by_name(name)?)
+}
+
+/// A frame with no rows and the non-`Null` columns of `schema`, for sources
+/// that are present but empty.
+fn empty_frame(schema: &Schema) -> LazyFrame {
+    let columns = schema
+        .iter()
+        .filter(|(_, dtype)| *dtype != &DataType::Null)
+        .map(|(name, dtype)| (name.clone(), dtype.clone()))
+        .collect::<Schema>();
+    DataFrame::empty_with_schema(&columns).lazy()
+}
+
+/// Runs `f` on `file` as an xlsx workbook for reading `what`, which the
+/// other formats don't have. Uses `workbook` when it is `file` and opens
+/// `file` otherwise.
+fn with_xlsx<R>(
+    workbook: Option<&Workbook>,
+    file: &Path,
+    what: &str,
+    f: impl FnOnce(&mut workbook::Opened) -> Result<R, ReadError>,
+) -> Result<R, ReadError> {
+    match extension(file).as_str() {
+        ext @ ("ods" | "xls" | "xlsb") => {
+            Err(format!("'.{ext}' workbooks have no {what}, only .xlsx workbooks do").into())
+        }
+        _ => match workbook.filter(|wb| wb.path() == file) {
+            Some(wb) => wb.with(f),
+            None => f(&mut workbook::Opened::new(calamine::open_workbook(file)?)),
+        },
+    }
+}
+
+/// The XML of `sheet` in `file` for reading `what`, which calamine doesn't
+/// read. Only .xlsx workbooks are read this way.
+fn sheet_xml(file: &Path, sheet: &str, what: &str) -> Result<SheetXml, ReadError> {
+    match extension(file).as_str() {
+        ext @ ("ods" | "xls" | "xlsb") => {
+            Err(format!("Reading {what} needs an .xlsx workbook, not a '.{ext}' one").into())
+        }
+        _ => SheetXml::open(file, sheet),
+    }
+}
+
+/// Replaces the cells of sheet column `column` from row `first_row` on with
+/// their hyperlink targets, empty for cells without a link. Rows above are
+/// the header and skipped rows, which keep their text.
+fn link_cells(
+    range: &mut calamine::Range<calamine::Data>,
+    column: u32,
+    first_row: u32,
+    xml: &SheetXml,
+) -> Result<(), ReadError> {
+    let (Some(start), Some(end)) = (range.start(), range.end()) else {
+        return Ok(());
+    };
+    if column < start.1 || column > end.1 || first_row > end.0 {
+        return Ok(());
+    }
+    let rows = first_row.max(start.0)..=end.0;
+    let mut links = xml.hyperlinks(column, rows.clone())?;
+    for row in rows {
+        let cell = links
+            .remove(&row)
+            .map_or(calamine::Data::Empty, calamine::Data::String);
+        range.set_value((row, column), cell);
+    }
+    Ok(())
+}
+
+/// Decompresses gzip data, reading every member of a concatenated file.
+fn gunzip(compressed: impl std::io::Read) -> Result<Vec<u8>, ReadError> {
+    let mut bytes = Vec::new();
+    flate2::read::MultiGzDecoder::new(compressed).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Fails with a message naming `file` when it is missing or empty, which
+/// formats with a footer would otherwise report obscurely.
+fn check_not_empty(file: &Path) -> Result<(), ReadError> {
+    match std::fs::metadata(file) {
+        Ok(metadata) if metadata.len() == 0 => {
+            Err(format!("File '{}' is empty", file.display()).into())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Cannot read file '{}': {e}", file.display()).into()),
+    }
+}
+
+/// Fails naming the declared columns absent from a file read with inference
+/// for the others. Removed columns may be absent.
+fn check_declared(schema: &Schema, file: &Schema) -> Result<(), ReadError> {
+    let names = file
+        .iter_names()
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>();
+    schema::partial(schema, &names).map(|_| ())
+}
+
+/// Reads single cells of a workbook by A1 reference. References without a
+/// sheet are looked up on `sheet`.
+fn read_named_cells(
+    workbook: Option<&Workbook>,
+    file: &Path,
+    cells: &[(String, String)],
+    sheet: Option<String>,
+) -> Result<BTreeMap<String, Option<calamine::Data>>, ReadError> {
+    let mut ranges = HashMap::new();
+    let mut values = BTreeMap::new();
+    for (name, reference) in cells {
+        let (cell_sheet, position) = a1::cell_reference(reference)?;
+        let Some(cell_sheet) = cell_sheet.or_else(|| sheet.clone()) else {
+            return Err(format!(
+                "Named cell '{name}' must name its sheet, as in 'Sheet1!{reference}'"
+            )
+            .into());
+        };
+        if !ranges.contains_key(&cell_sheet) {
+            let range = worksheet_range(workbook, file, &cell_sheet)?;
+            ranges.insert(cell_sheet.clone(), range);
+        }
+        let value = ranges[&cell_sheet]
+            .get_value(position)
+            .filter(|value| !matches!(value, calamine::Data::Empty))
+            .cloned();
+        values.insert(name.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Checks that the names of a header row are the schema columns, in order.
+fn check_header(names: &[String], schema: &Schema) -> Result<(), ReadError> {
+    if names.len() != schema.len() {
+        return Err(format!(
+            "Header has {} columns but the schema declares {} columns",
+            names.len(),
+            schema.len()
+        )
+        .into());
+    }
+    for (index, (header, name)) in names.iter().zip(schema.iter_names()).enumerate() {
+        if header.trim() != name.as_str() {
+            return Err(ReadError::HeaderMismatch {
+                index,
+                expected: name.to_string(),
+                found: header.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Builds a frame from a range of cells, skipping its first rows as the
+/// options say. Cells are located in errors on the sheet named by
+/// `location`.
+fn range_to_frame(
+    range: &calamine::Range<calamine::Data>,
+    schema: &Schema,
+    options: &ReadOptions,
+    header: bool,
+    location: String,
+) -> Result<DataFrame, ReadError> {
+    let skip = options.skip_rows();
+    let (first_row, first_col) = range.start().unwrap_or((0, 0));
+    let origin = CellOrigin::new(location, (first_row + skip as u32, first_col));
+    let rows = range.rows().skip(skip).map(Ok::<_, Infallible>);
+    rows_to_frame(rows, schema, options, header, origin)
+}
+
+/// Builds a frame from the rows of a sheet, resolving the schema against
+/// their first row when it is a `header`: the types of its columns come from
+/// the dictionary in dictionary mode, where the first row always names the
+/// columns, undeclared ones are inferred, columns are found by name, or the
+/// names are checked in order.
+fn rows_to_frame<I, R, E>(
+    rows: I,
+    schema: &Schema,
+    options: &ReadOptions,
+    header: bool,
+    mut origin: CellOrigin,
+) -> Result<DataFrame, ReadError>
+where
+    I: IntoIterator<Item = Result<R, E>>,
+    R: AsRef<[calamine::Data]>,
+    E: Into<ReadError>,
+{
+    let mut rows = rows.into_iter();
+    if !header && !options.schema_dictionary() {
+        return build_frame(rows, schema, options, &origin);
+    }
+    let header = match rows.next() {
+        Some(row) => header_names(row.map_err(Into::into)?.as_ref()),
+        None if options.schema_dictionary() => Vec::new(),
+        None => return build_frame(rows, schema, options, &origin),
+    };
+    origin.start.0 += 1;
+    if options.schema_dictionary() {
+        let schema = schema::from_dictionary(schema, &header)?;
+        return build_frame(rows, &schema, options, &origin);
+    }
+    if options.infer_schema().is_some() {
+        let schema = schema::partial(schema, &header)?;
+        return build_frame(rows, &schema, options, &origin);
+    }
+    if options.match_by_name() {
+        let rows = reorder_by_name(rows, &header, schema, &mut origin)?;
+        return build_frame(rows, schema, options, &origin);
+    }
+    check_header(&header, schema)?;
+    build_frame(rows, schema, options, &origin)
+}
+
+/// Reorders the cells of each row to the schema's column order, finding
+/// each column by its name in `header`. The cells' source columns are
+/// recorded in `origin`.
+fn reorder_by_name<I, R, E>(
+    rows: I,
+    header: &[String],
+    schema: &Schema,
+    origin: &mut CellOrigin,
+) -> Result<impl Iterator<Item = Result<Vec<calamine::Data>, E>>, ReadError>
+where
+    I: IntoIterator<Item = Result<R, E>>,
+    R: AsRef<[calamine::Data]>,
+{
+    let positions = schema::positions(schema, header)?;
+    // cells picked from non-adjacent columns keep their source columns
+    let source = |position: usize| match &origin.columns {
+        Some(columns) => columns.get(position).copied().unwrap_or(origin.start.1),
+        None => origin.start.1 + position as u32,
+    };
+    origin.columns = Some(
+        positions
+            .iter()
+            .map(|position| source(position.unwrap_or(0)))
+            .collect(),
+    );
+    Ok(rows.into_iter().map(move |row| {
+        row.map(|row| {
+            positions
+                .iter()
+                .map(|position| {
+                    position
+                        .and_then(|p| row.as_ref().get(p))
+                        .cloned()
+                        .unwrap_or(calamine::Data::Empty)
+                })
+                .collect()
+        })
+    }))
+}
+
+/// Where the rows handed to [`build_frame`] start, to locate the cells that
+/// fail to read.
+struct CellOrigin {
+    /// The file, and sheet or table, the cells are read from.
+    label: String,
+    /// Zero-based position of the first cell.
+    start: (u32, u32),
+    /// Zero-based source column of each cell in a row, when the cells were
+    /// picked from non-adjacent columns.
+    columns: Option<Vec<u32>>,
+}
+
+impl CellOrigin {
+    fn new(label: String, start: (u32, u32)) -> Self {
+        Self {
+            label,
+            start,
+            columns: None,
+        }
+    }
+
+    /// Attaches the source position of the cell at `row` and `column` of the
+    /// rows read to `error`. Transposed rows are source columns.
+    fn locate(&self, row: usize, column: usize, transposed: bool, error: ReadError) -> ReadError {
+        let (row, column) = if transposed {
+            (column, row)
+        } else {
+            (row, column)
+        };
+        let column = match &self.columns {
+            Some(columns) => columns.get(column).copied().unwrap_or(column as u32),
+            None => self.start.1 + column as u32,
+        };
+        ReadError::AtCell {
+            location: self.label.clone(),
+            row: self.start.0 + row as u32 + 1,
+            column: column + 1,
+            source: Box::new(error),
+        }
+    }
+}
+
+/// Resolves a workbook's defined name to the sheet and bounds of the single
+/// range it refers to.
+fn defined_name_range(
+    file: &Path,
+    name: &str,
+) -> Result<(String, (u32, u32), (u32, u32)), ReadError> {
+    let wb = calamine::open_workbook_auto(file)?;
+    let (_, formula) = wb
+        .defined_names()
+        .iter()
+        .find(|(defined, _)| defined.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Defined name '{name}' not found in '{}'", file.display()))?;
+    let formula = formula.trim().trim_start_matches('=');
+    let mut quoted = false;
+    if formula.chars().any(|c| {
+        if c == '\'' {
+            quoted = !quoted;
         }
-        Ok(schema)
+        c == ',' && !quoted
+    }) {
+        return Err(format!(
+            "Defined name '{name}' refers to several areas ({formula}), only single ranges can be read"
+        )
+        .into());
     }
-    fn metadata(&self) -> Self::Metadata;
-    fn raw_schema(&self) -> &[(String, String)];
+    let (sheet, start, end) = a1::range_reference(formula)
+        .map_err(|_| format!("Defined name '{name}' does not refer to a range: {formula}"))?;
+    let sheet = sheet.ok_or_else(|| format!("Defined name '{name}' names no sheet: {formula}"))?;
+    Ok((sheet, start, end))
 }
 
-/// Reads a text or csv file.
-impl Read for &'_ Reader<'_, PhantomTxtReader> {
-    type Metadata = ();
-    fn read(&self, file: &Path) -> Result<LazyFrame, Box<dyn std::error::Error>> {
-        let schema = self.schema()?;
-        let ignore_columns = schema
-            .iter()
-            .filter_map(|val| {
-                if val.1 == &DataType::Null {
-                    None
-                } else {
-                    Some(Expr::Column(val.0.clone()))
-                }
-            })
-            .collect::<Vec<_>>();
-        LazyCsvReader::new(PlPath::from_str(file.to_str().ok_or("Invalid file path")?))
-            .with_has_header(self.inner.has_header())
-            .with_separator(self.inner.separator())
-            .with_rechunk(self.inner.rechunk())
-            .with_eol_char(self.inner.eol_char())
-            .with_ignore_errors(self.inner.ignore_errors())
-            .with_null_values(
-                self.inner.null_values().map(|v| {
-                    NullValues::AllColumns(v.iter().map(|v| PlSmallStr::from(v)).collect())
-                }),
-            )
-            .with_quote_char(self.inner.quote_char())
-            .with_low_memory(self.inner.low_memory())
-            .with_encoding(if self.inner.enforce_utf8() {
-                CsvEncoding::Utf8
+/// The cells of a used range row in the `selected` absolute columns, empty
+/// where the row doesn't reach. The row starts at column `first_col`.
+fn select_cells(row: &[calamine::Data], selected: &[u32], first_col: u32) -> Vec<calamine::Data> {
+    selected
+        .iter()
+        .map(|index| {
+            index
+                .checked_sub(first_col)
+                .and_then(|i| row.get(i as usize))
+                .cloned()
+                .unwrap_or(calamine::Data::Empty)
+        })
+        .collect()
+}
+
+/// The text of a header row's cells.
+fn header_names(cells: &[calamine::Data]) -> Vec<String> {
+    cells.iter().map(|cell| cell.to_string()).collect()
+}
+
+/// Swaps rows and columns of a grid of cells, padding ragged rows with empty
+/// cells.
+fn transpose_rows(rows: &[Vec<calamine::Data>]) -> Vec<Vec<calamine::Data>> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (0..width)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row.get(column).cloned().unwrap_or(calamine::Data::Empty))
+                .collect()
+        })
+        .collect()
+}
+
+fn columns_to_frame<I, R, E>(
+    rows: I,
+    schema: &Schema,
+    options: &ReadOptions,
+    origin: &CellOrigin,
+) -> Result<DataFrame, ReadError>
+where
+    I: IntoIterator<Item = Result<R, E>>,
+    R: AsRef<[calamine::Data]>,
+    E: Into<ReadError>,
+{
+    let schema_len = schema.len();
+    let mut columns = schema
+        .iter_values()
+        .map(|dtype| {
+            if dtype == &DataType::Null {
+                Vec::<AnyValue>::with_capacity(0)
             } else {
-                CsvEncoding::LossyUtf8
-            })
-            .with_skip_rows(self.inner.skip_lines())
-            .with_missing_is_null(self.inner.missing_is_null())
-            .with_schema(Some(schema.into()))
-            .finish()
-            .map(|lf| lf.select(ignore_columns))
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Vec::<AnyValue>::with_capacity(1000)
+            }
+        })
+        .collect::<Vec<_>>();
+    for (index, row) in rows.into_iter().enumerate() {
+        let row = row.map_err(Into::into)?;
+        let row = row.as_ref();
+        if row.len() > schema_len
+            || row.len() < schema_len && options.column_count_policy() == ColumnCountPolicy::Error
+        {
+            // located at the first missing or extra cell
+            let error = ReadError::ColumnCountMismatch {
+                expected: schema_len,
+                found: row.len(),
+            };
+            let column = row.len().min(schema_len);
+            return Err(origin.locate(index, column, options.transpose(), error));
+        }
+        for (column, dtype) in schema.iter_values().enumerate() {
+            if dtype == &DataType::Null {
+                continue;
+            }
+            match row.get(column) {
+                Some(value) => dtconv::cast_excel_type_to_polars_type(
+                    value,
+                    dtype,
+                    &mut columns[column],
+                    options,
+                )
+                .map_err(|e| origin.locate(index, column, options.transpose(), e))?,
+                // short rows are padded with nulls
+                None => columns[column].push(AnyValue::Null),
+            }
+        }
     }
-
-    fn metadata(&self) -> Self::Metadata {
-        todo!()
+    let mut df = DataFrame::default();
+    for ((name, dt), values) in schema.iter().zip(columns.into_iter()) {
+        if dt == &DataType::Null {
+            continue;
+        } else {
+            df.with_column(Series::new(name.clone(), values).cast(dt)?)?;
+        }
     }
+    Ok(df)
+}
 
-    fn raw_schema(&self) -> &[(String, String)] {
-        self.inner.schema()
+/// Ends a range of full columns, which runs to the last row of the sheet,
+/// at the last row of the used range.
+fn clamp_end(range: &calamine::Range<calamine::Data>, end: (u32, u32)) -> (u32, u32) {
+    if end.0 != a1::LAST_ROW {
+        return end;
     }
+    (range.end().map_or(0, |(row, _)| row), end.1)
+}
+
+/// Assembles the rows of `start..=end` from a stream of worksheet cells in
+/// row-major order, so the sheet is never held in memory as a whole. Cells
+/// absent from the stream are empty, except that a range of full columns
+/// ends with the last cell.
+fn stream_rows<F, E>(
+    mut next_cell: F,
+    start: (u32, u32),
+    end: (u32, u32),
+) -> impl Iterator<Item = Result<Vec<calamine::Data>, E>>
+where
+    F: FnMut() -> Result<Option<((u32, u32), calamine::Data)>, E>,
+{
+    let width = (end.1.saturating_sub(start.1) + 1) as usize;
+    let mut next_row = start.0;
+    // first cell of a later row, read while finishing the current one
+    let mut pending: Option<((u32, u32), calamine::Data)> = None;
+    let mut exhausted = false;
+    std::iter::from_fn(move || {
+        if next_row > end.0 || (end.0 == a1::LAST_ROW && exhausted && pending.is_none()) {
+            return None;
+        }
+        let mut row = vec![calamine::Data::Empty; width];
+        loop {
+            let cell = match pending.take() {
+                Some(cell) => Some(cell),
+                None if exhausted => None,
+                None => match next_cell() {
+                    Ok(Some(cell)) => Some(cell),
+                    Ok(None) => {
+                        exhausted = true;
+                        None
+                    }
+                    Err(e) => return Some(Err(e)),
+                },
+            };
+            match cell {
+                Some(((r, c), value)) if r == next_row => {
+                    if (start.1..=end.1).contains(&c) {
+                        row[(c - start.1) as usize] = value;
+                    }
+                }
+                // above the range
+                Some(((r, _), _)) if r < next_row => {}
+                Some(cell) => {
+                    pending = Some(cell);
+                    break;
+                }
+                None => break,
+            }
+        }
+        next_row += 1;
+        Some(Ok(row))
+    })
 }
 
 /// Reads a specific pivot table cache from an Excel file.
 impl Read for &'_ Reader<'_, PhantomPivotTableReader> {
     type Metadata = (String, String);
-    fn read(&self, file: &Path) -> Result<LazyFrame, Box<dyn std::error::Error>> {
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
         let meta = self.metadata();
-        let mut wb: Xlsx<_> = calamine::open_workbook(file)?;
-        let pivot_tables = wb.pivot_tables()?;
-
-        let schema = self.schema()?;
-        let schema_len = schema.len();
-        // next for each column in schema
-        let mut cycle_columns = (0..schema_len).cycle();
-        let mut columns = Vec::with_capacity(schema.len());
-
-        let mut rows = wb
-            .pivot_table_data(&pivot_tables, &meta.0, &meta.1)
-            .map_err(|e| Box::new(e))?;
-        if let Some(headers) = rows.next() {
-            for header in headers? {
-                let column = unsafe { cycle_columns.next().unwrap_unchecked() };
-                let (name, dtype) = unsafe { schema.get_at_index(column).unwrap_unchecked() };
-
-                // headers should always be strings
-                if calamine::Data::String(name.to_string()) != header {
-                    panic!(
-                        "Pivot table header '{}' does not match expected schema column name '{}'",
-                        header, name
-                    );
-                } else if dtype == &DataType::Null {
-                    columns.push(Vec::<AnyValue>::with_capacity(0));
-                } else {
-                    columns.push(Vec::<AnyValue>::with_capacity(1000));
-                }
+        with_xlsx(self.options.workbook(), file, "pivot tables", |wb| {
+            let wb = wb.xlsx();
+            let pivot_tables = wb.pivot_tables()?;
+            let schema = self.schema()?;
+            let mut rows = wb
+                .pivot_table_data(&pivot_tables, &meta.0, &meta.1)?
+                .peekable();
+            if rows.peek().is_none() {
+                // empty pivot table, return empty dataframe with schema
+                return Ok(empty_frame(&schema));
             }
-            for data in rows {
-                for value in data?.iter() {
-                    // Safety: cycle_schema is guaranteed to have enough elements because empty schema is checked earlier
-                    let column = unsafe { cycle_columns.next().unwrap_unchecked() };
-                    let (_, dtype) = unsafe { schema.get_at_index(column).unwrap_unchecked() };
+            // rows are counted from the pivot table's header
+            let origin = CellOrigin::new(self.source_label(file), (0, 0));
+            Ok(rows_to_frame(rows, &schema, &self.options, true, origin)?.lazy())
+        })
+    }
 
-                    if dtype == &DataType::Null {
-                        continue;
-                    } else {
-                        dtconv::cast_excel_type_to_polars_type(value, dtype, &mut columns[column])?;
-                    }
-                }
-            }
-            let mut df = DataFrame::default();
-            for ((name, dt), values) in schema.into_iter().zip(columns.into_iter()) {
-                if dt == DataType::Null {
-                    continue;
-                } else {
-                    df.with_column(Series::new(name, values).cast(&dt)?)?;
-                }
+    fn source_label(&self, file: &Path) -> String {
+        let meta = self.metadata();
+        format!("{}!{}/{}", file.display(), meta.0, meta.1)
+    }
+
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        let meta = self.metadata();
+        with_xlsx(self.options.workbook(), file, "pivot tables", |wb| {
+            let wb = wb.xlsx();
+            let pivot_tables = wb.pivot_tables()?;
+            let mut rows = wb.pivot_table_data(&pivot_tables, &meta.0, &meta.1)?;
+            match rows.next() {
+                Some(headers) => Ok(Some(header_names(&headers?))),
+                None => Ok(None),
             }
-            Ok(df.lazy())
-        } else {
-            // empty pivot table, return empty dataframe with schema
-            Ok(LazyFrame::default().with_columns(
-                schema
-                    .iter()
-                    .map(|s| Expr::Column(s.0.clone()).cast(DataTypeExpr::from(s.1.clone())))
-                    .collect::<Vec<Expr>>(),
-            ))
-        }
+        })
+    }
+
+    fn sheet(&self, _file: &Path) -> Result<Option<String>, ReadError> {
+        Ok(Some(self.metadata().0))
     }
 
     fn metadata(&self) -> Self::Metadata {
@@ -221,46 +2255,117 @@ impl Read for &'_ Reader<'_, PhantomPivotTableReader> {
     fn raw_schema(&self) -> &[(String, String)] {
         self.inner.schema()
     }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
 }
 
 /// Reads a specific table from an Excel file.
 impl Read for &'_ Reader<'_, PhantomTableReader> {
     type Metadata = String;
-    fn read(&self, file: &Path) -> Result<LazyFrame, Box<dyn std::error::Error>> {
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
         let meta = self.metadata();
-        let mut wb: Xlsx<_> = calamine::open_workbook(file)?;
-        wb.load_tables()?;
-        let tables = wb.table_by_name(meta.as_str())?;
+        with_xlsx(self.options.workbook(), file, "tables", |wb| {
+            let wb = wb.with_tables()?;
+            let tables = table_by_name(wb, meta.as_str())?;
 
-        let schema = self.schema()?;
-        let schema_len = schema.len();
-        // next for each column in schema
-        let mut cycle_columns = (0..schema_len).cycle();
-        let mut columns = Vec::with_capacity(schema.len());
-        for _ in 0..schema.len() {
-            columns.push(Vec::<AnyValue>::with_capacity(1000));
-        }
-        for row in tables.data().rows() {
-            for col in row {
-                // Safety: cycle_schema is guaranteed to have enough elements because empty schema is checked earlier
-                let column = unsafe { cycle_columns.next().unwrap_unchecked() };
-                let (_, dtype) = unsafe { schema.get_at_index(column).unwrap_unchecked() };
-                if dtype == &DataType::Null {
-                    continue;
+            let mut schema = self.schema()?;
+            if self.options.schema_dictionary() {
+                schema = schema::from_dictionary(&schema, tables.columns())?;
+            } else if self.options.infer_schema().is_some() {
+                schema = schema::partial(&schema, tables.columns())?;
+            }
+            let rows = tables.data().rows().map(Ok::<_, Infallible>);
+            let mut origin = CellOrigin::new(
+                format!("{}!{}", file.display(), tables.sheet_name()),
+                tables.data().start().unwrap_or((0, 0)),
+            );
+            if self.options.match_by_name()
+                && !self.options.schema_dictionary()
+                && self.options.infer_schema().is_none()
+            {
+                let rows = reorder_by_name(rows, tables.columns(), &schema, &mut origin)?;
+                return Ok(build_frame(rows, &schema, &self.options, &origin)?.lazy());
+            }
+            Ok(build_frame(rows, &schema, &self.options, &origin)?.lazy())
+        })
+    }
+
+    /// Flags table columns that contain formulas, whose values are the ones
+    /// cached at Excel's last recalculation.
+    fn inspect(&self, file: &Path, report: &mut ReadReport) -> Result<(), ReadError> {
+        if !self.options.flag_formula_columns() {
+            return Ok(());
+        }
+        with_xlsx(self.options.workbook(), file, "tables", |wb| {
+            let wb = wb.with_tables()?;
+            let table = table_by_name(wb, self.metadata().as_str())?;
+            let (Some(start), Some(end)) = (table.data().start(), table.data().end()) else {
+                return Ok(());
+            };
+            let formulas = wb.worksheet_formula(table.sheet_name())?;
+            // schema columns found by name may sit anywhere in the table
+            let by_name = self.options.match_by_name()
+                || self.options.schema_dictionary()
+                || self.options.infer_schema().is_some();
+            for (index, name) in self.schema()?.iter_names().enumerate() {
+                let position = if by_name {
+                    table
+                        .columns()
+                        .iter()
+                        .position(|column| column.trim() == name.as_str())
                 } else {
-                    dtconv::cast_excel_type_to_polars_type(col, dtype, &mut columns[column])?;
+                    Some(index)
+                };
+                let Some(position) = position else {
+                    continue;
+                };
+                let column = start.1 + position as u32;
+                let has_formula = (start.0..=end.0).any(|row| {
+                    formulas
+                        .get_value((row, column))
+                        .is_some_and(|formula| !formula.is_empty())
+                });
+                if has_formula {
+                    report.formula_columns.push(name.clone());
                 }
             }
-        }
-        let mut df = DataFrame::default();
-        for ((name, dt), values) in schema.into_iter().zip(columns.into_iter()) {
-            if dt == DataType::Null {
-                continue;
-            } else {
-                df.with_column(Series::new(name, values).cast(&dt)?)?;
+            if self.options.reject_formula_keys()
+                && let Some(key) = self.options.key_columns().iter().find(|key| {
+                    report
+                        .formula_columns
+                        .iter()
+                        .any(|column| column.as_str() == key.as_str())
+                })
+            {
+                return Err(format!(
+                "Key column '{key}' is formula-backed and holds values cached at the last Excel recalculation"
+            )
+            .into());
             }
-        }
-        Ok(df.lazy())
+            Ok(())
+        })
+    }
+
+    fn source_label(&self, file: &Path) -> String {
+        format!("{}!{}", file.display(), self.metadata())
+    }
+
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        with_xlsx(self.options.workbook(), file, "tables", |wb| {
+            let wb = wb.with_tables()?;
+            let table = table_by_name(wb, self.metadata().as_str())?;
+            Ok(Some(table.columns().to_vec()))
+        })
+    }
+
+    fn sheet(&self, file: &Path) -> Result<Option<String>, ReadError> {
+        with_xlsx(self.options.workbook(), file, "tables", |wb| {
+            let wb = wb.with_tables()?;
+            let table = table_by_name(wb, self.metadata().as_str())?;
+            Ok(Some(table.sheet_name().to_string()))
+        })
     }
 
     fn metadata(&self) -> Self::Metadata {
@@ -274,45 +2379,202 @@ impl Read for &'_ Reader<'_, PhantomTableReader> {
     fn raw_schema(&self) -> &[(String, String)] {
         self.inner.schema()
     }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+}
+
+/// Reads a specific range from a sheet in an Excel (`.xlsx`, `.xlsb`,
+/// `.xls`) or OpenDocument file. A range with all bounds zero stands for the
+/// sheet's whole used range, whose first row is a header checked against the
+/// schema.
+impl Reader<'_, PhantomSheetRangeReader> {
+    /// The cells read from the sheet: the range of its autofilter when it is
+    /// read, its used range when sheet columns are selected or the bounds
+    /// are all zero, the bounds otherwise.
+    fn cells(&self, file: &Path) -> Result<calamine::Range<calamine::Data>, ReadError> {
+        let (name, start, end) = self.metadata();
+        let sheet = worksheet_range(self.options.workbook(), file, &name)?;
+        if self.options.auto_filter_range() {
+            let (start, end) = sheet_xml(file, &name, "autofilters")?
+                .auto_filter()?
+                .ok_or_else(|| format!("Sheet '{name}' has no autofilter"))?;
+            return Ok(sheet.range(start, clamp_end(&sheet, end)));
+        }
+        if self.options.sheet_columns().is_some() || self.whole_sheet() {
+            return Ok(sheet);
+        }
+        Ok(sheet.range(start, clamp_end(&sheet, end)))
+    }
+
+    /// Whether the bounds are all zero, which reads the whole used range.
+    fn whole_sheet(&self) -> bool {
+        let (_, start, end) = self.metadata();
+        self.options.range().is_none() && start == (0, 0) && end == (0, 0)
+    }
+
+    /// Whether the first of [`Self::cells`] not skipped is a header row, as
+    /// it always is for the whole used range and the autofilter range.
+    fn reads_header(&self) -> bool {
+        self.options.range_header()
+            || self.options.auto_filter_range()
+            || (self.whole_sheet() && self.options.sheet_columns().is_none())
+    }
+
+    /// The zero-based sheet columns picked from `cells`, `None` when all of
+    /// them are read: the selected sheet columns, or every column of the
+    /// cells, less those of collapsed outline groups when they are skipped.
+    fn selected_columns(
+        &self,
+        file: &Path,
+        cells: &calamine::Range<calamine::Data>,
+    ) -> Result<Option<Vec<u32>>, ReadError> {
+        let selected = match self.options.sheet_columns() {
+            Some(selected) => selected
+                .iter()
+                .map(|c| a1::column_index(c))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => match (cells.start(), cells.end()) {
+                (Some(start), Some(end)) if self.options.skip_collapsed_columns() => {
+                    (start.1..=end.1).collect()
+                }
+                _ => return Ok(None),
+            },
+        };
+        if !self.options.skip_collapsed_columns() {
+            return Ok(Some(selected));
+        }
+        let collapsed = sheet_xml(file, &self.metadata().0, "column outlines")?.collapsed_columns();
+        Ok(Some(
+            selected
+                .into_iter()
+                .filter(|column| !collapsed.contains(column))
+                .collect(),
+        ))
+    }
 }
 
-/// Reads a specific range from a sheet in an Excel file.
 impl Read for &'_ Reader<'_, PhantomSheetRangeReader> {
     type Metadata = (String, (u32, u32), (u32, u32));
-    fn read(&self, file: &Path) -> Result<LazyFrame, Box<dyn std::error::Error>> {
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
         let meta = self.metadata();
-        let mut wb: Xlsx<_> = calamine::open_workbook(file)?;
         let schema = self.schema()?;
-        let schema_len = schema.len();
-        // next for each column in schema
-        let mut cycle_columns = (0..schema_len).cycle();
-        let mut columns = Vec::with_capacity(schema.len());
-        let reader = wb.worksheet_range(meta.0.as_str())?.range(meta.1, meta.2);
-        for row in reader.rows() {
-            for col in row {
-                // Safety: cycle_schema is guaranteed to have enough elements because empty schema is checked earlier
-                let column = unsafe { cycle_columns.next().unwrap_unchecked() };
-                let (_, dtype) = unsafe { schema.get_at_index(column).unwrap_unchecked() };
-                if dtype == &DataType::Null {
-                    continue;
-                } else {
-                    dtconv::cast_excel_type_to_polars_type(col, dtype, &mut columns[column])?;
-                }
-            }
+        // only xlsx workbooks can be streamed cell by cell
+        if self.options.stream_cells()
+            && !self.whole_sheet()
+            && self.options.sheet_columns().is_none()
+            && self.options.hyperlink_column().is_none()
+            && !self.options.auto_filter_range()
+            && !self.options.skip_collapsed_columns()
+            && extension(file) == "xlsx"
+        {
+            return with_xlsx(self.options.workbook(), file, "cell streams", |wb| {
+                let mut cells = wb.xlsx().worksheet_cells_reader(meta.0.as_str())?;
+                let rows = stream_rows(
+                    move || {
+                        cells.next_cell().map(|cell| {
+                            cell.map(|cell| {
+                                (
+                                    cell.get_position(),
+                                    calamine::Data::from(cell.get_value().clone()),
+                                )
+                            })
+                        })
+                    },
+                    meta.1,
+                    meta.2,
+                )
+                .skip(self.options.skip_rows());
+                let mut origin = CellOrigin::new(self.source_label(file), meta.1);
+                origin.start.0 += self.options.skip_rows() as u32;
+                let header = self.options.range_header();
+                Ok(rows_to_frame(rows, &schema, &self.options, header, origin)?.lazy())
+            });
         }
-        let mut df = DataFrame::default();
-        for ((name, dt), values) in schema.into_iter().zip(columns.into_iter()) {
-            if dt == DataType::Null {
-                continue;
-            } else {
-                df.with_column(Series::new(name, values).cast(&dt)?)?;
-            }
+        let mut sheet = self.cells(file)?;
+        let header = self.reads_header();
+        if let Some(column) = self.options.hyperlink_column() {
+            let xml = sheet_xml(file, &meta.0, "hyperlinks")?;
+            // the dictionary names the columns in the first row, header or not
+            let names_row = header || self.options.schema_dictionary();
+            let first_row = sheet.start().map_or(0, |(row, _)| row)
+                + (self.options.skip_rows() + usize::from(names_row)) as u32;
+            link_cells(&mut sheet, a1::column_index(column)?, first_row, &xml)?;
         }
+        let df = if let Some(selected) = self.selected_columns(file, &sheet)? {
+            // the dictionary and inference type the selected columns by name
+            let typed_by_name =
+                self.options.schema_dictionary() || self.options.infer_schema().is_some();
+            if self.options.sheet_columns().is_some()
+                && !typed_by_name
+                && selected.len() != schema.len()
+            {
+                return Err(format!(
+                    "{} sheet columns selected but the schema declares {} columns",
+                    selected.len(),
+                    schema.len()
+                )
+                .into());
+            }
+            // selected columns are absolute, rows of the cells start at their first column
+            let first_col = sheet.start().map(|(_, col)| col).unwrap_or(0);
+            let rows = sheet
+                .rows()
+                .skip(self.options.skip_rows())
+                .map(|row| Ok::<_, Infallible>(select_cells(row, &selected, first_col)));
+            let mut origin =
+                CellOrigin::new(self.source_label(file), sheet.start().unwrap_or((0, 0)));
+            origin.start.0 += self.options.skip_rows() as u32;
+            origin.columns = Some(selected.clone());
+            rows_to_frame(rows, &schema, &self.options, header, origin)?
+        } else {
+            range_to_frame(
+                &sheet,
+                &schema,
+                &self.options,
+                header,
+                self.source_label(file),
+            )?
+        };
         Ok(df.lazy())
     }
 
+    fn source_label(&self, file: &Path) -> String {
+        format!("{}!{}", file.display(), self.metadata().0)
+    }
+
+    /// The first row of the range, or of the selected sheet columns, when it
+    /// is a header.
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        if !self.reads_header() {
+            return Ok(None);
+        }
+        let sheet = self.cells(file)?;
+        let row = sheet.rows().nth(self.options.skip_rows());
+        Ok(match self.selected_columns(file, &sheet)? {
+            Some(selected) => {
+                let first_col = sheet.start().map(|(_, col)| col).unwrap_or(0);
+                row.map(|row| header_names(&select_cells(row, &selected, first_col)))
+            }
+            None => row.map(header_names),
+        })
+    }
+
+    fn sheet(&self, _file: &Path) -> Result<Option<String>, ReadError> {
+        Ok(Some(self.metadata().0))
+    }
+
+    fn check_source(&self, file: &Path) -> Result<(), ReadError> {
+        worksheet_range(self.options.workbook(), file, &self.metadata().0).map(|_| ())
+    }
+
     fn metadata(&self) -> Self::Metadata {
         if let Some(meta) = self.inner.kind().get_sheet_range_info() {
+            // the reference is checked when the options are validated
+            if let Some(Ok((sheet, start, end))) = self.options.range().map(a1::range_reference) {
+                return (sheet.unwrap_or(meta.sheet_name.to_string()), start, end);
+            }
             (
                 meta.sheet_name.to_string(),
                 (meta.start_row as u32, meta.start_col as u32),
@@ -326,6 +2588,256 @@ impl Read for &'_ Reader<'_, PhantomSheetRangeReader> {
     fn raw_schema(&self) -> &[(String, String)] {
         self.inner.schema()
     }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+}
+
+/// Reads the range a workbook's defined name refers to, so the coordinates
+/// can move with the data. Selected by [`ReadOptions::with_defined_name`].
+impl Read for &'_ Reader<'_, PhantomDefinedNameReader> {
+    type Metadata = String;
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        let (sheet, start, end) = defined_name_range(file, &self.metadata())?;
+        let sheet_range = worksheet_range(self.options.workbook(), file, &sheet)?;
+        let range = sheet_range.range(start, clamp_end(&sheet_range, end));
+        Ok(range_to_frame(
+            &range,
+            &self.schema()?,
+            &self.options,
+            self.options.range_header(),
+            format!("{}!{sheet}", file.display()),
+        )?
+        .lazy())
+    }
+
+    fn source_label(&self, file: &Path) -> String {
+        format!("{}!{}", file.display(), self.metadata())
+    }
+
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        if !self.options.range_header() {
+            return Ok(None);
+        }
+        let (sheet, start, end) = defined_name_range(file, &self.metadata())?;
+        let sheet_range = worksheet_range(self.options.workbook(), file, &sheet)?;
+        let range = sheet_range.range(start, clamp_end(&sheet_range, end));
+        Ok(range.rows().nth(self.options.skip_rows()).map(header_names))
+    }
+
+    fn sheet(&self, file: &Path) -> Result<Option<String>, ReadError> {
+        Ok(Some(defined_name_range(file, &self.metadata())?.0))
+    }
+
+    fn check_source(&self, file: &Path) -> Result<(), ReadError> {
+        let (sheet, _, _) = defined_name_range(file, &self.metadata())?;
+        worksheet_range(self.options.workbook(), file, &sheet).map(|_| ())
+    }
+
+    fn metadata(&self) -> Self::Metadata {
+        if let Some(name) = self.options.defined_name() {
+            name.to_string()
+        } else {
+            panic!("No defined name for DefinedNameReader");
+        }
+    }
+
+    fn raw_schema(&self) -> &[(String, String)] {
+        self.inner.schema()
+    }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+}
+
+/// Reads the same range from several sheets with identical layouts, such as
+/// monthly tabs, stacked in the order given. Selected by
+/// [`ReadOptions::with_sheets`]; the `SheetRange` supplies the bounds.
+impl Reader<'_, PhantomMultiSheetReader> {
+    /// The bounds read from every sheet, and whether they stand for the whole
+    /// used range.
+    fn bounds(&self) -> Result<(bool, (u32, u32), (u32, u32)), ReadError> {
+        let Some(bounds) = self.inner.kind().get_sheet_range_info() else {
+            panic!("Invalid QaKind for MultiSheetReader");
+        };
+        let mut start = (bounds.start_row as u32, bounds.start_col as u32);
+        let mut end = (bounds.end_row as u32, bounds.end_col as u32);
+        let whole_sheet = self.options.range().is_none() && start == (0, 0) && end == (0, 0);
+        if let Some(range) = self.options.range() {
+            (_, start, end) = a1::range_reference(range)?;
+        }
+        Ok((whole_sheet, start, end))
+    }
+}
+
+impl Read for &'_ Reader<'_, PhantomMultiSheetReader> {
+    type Metadata = Vec<String>;
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        let schema = self.schema()?;
+        let (whole_sheet, start, end) = self.bounds()?;
+        let mut frames = Vec::new();
+        for sheet in self.metadata() {
+            let read = || -> Result<DataFrame, ReadError> {
+                let range = worksheet_range(self.options.workbook(), file, &sheet)?;
+                let location = format!("{}!{sheet}", file.display());
+                if whole_sheet {
+                    range_to_frame(&range, &schema, &self.options, true, location)
+                } else {
+                    range_to_frame(
+                        &range.range(start, clamp_end(&range, end)),
+                        &schema,
+                        &self.options,
+                        self.options.range_header(),
+                        location,
+                    )
+                }
+            };
+            let df = read().map_err(|e| format!("Sheet '{sheet}': {e}"))?;
+            let lf = match self.options.source_column() {
+                Some(name) => df
+                    .lazy()
+                    .with_column(lit(format!("{}!{sheet}", file.display())).alias(name)),
+                None => df.lazy(),
+            };
+            frames.push(lf);
+        }
+        Ok(concat(frames, UnionArgs::default())?)
+    }
+
+    fn source_label(&self, file: &Path) -> String {
+        format!("{}!{}", file.display(), self.metadata().join(","))
+    }
+
+    /// Each row's source is its own sheet.
+    fn labels_sources(&self) -> bool {
+        true
+    }
+
+    /// The header row of the first sheet. The other sheets' headers are
+    /// checked against the schema as they are read.
+    fn header(&self, file: &Path) -> Result<Option<Vec<String>>, ReadError> {
+        let (whole_sheet, start, end) = self.bounds()?;
+        let Some(sheet) = self.metadata().into_iter().next() else {
+            return Ok(None);
+        };
+        if !self.options.range_header() && !whole_sheet {
+            return Ok(None);
+        }
+        let range = worksheet_range(self.options.workbook(), file, &sheet)?;
+        let skip = self.options.skip_rows();
+        if whole_sheet {
+            return Ok(range.rows().nth(skip).map(header_names));
+        }
+        let range = range.range(start, clamp_end(&range, end));
+        Ok(range.rows().nth(skip).map(header_names))
+    }
+
+    fn check_source(&self, file: &Path) -> Result<(), ReadError> {
+        for sheet in self.metadata() {
+            worksheet_range(self.options.workbook(), file, &sheet)?;
+        }
+        Ok(())
+    }
+
+    fn metadata(&self) -> Self::Metadata {
+        self.options.sheets().to_vec()
+    }
+
+    fn raw_schema(&self) -> &[(String, String)] {
+        self.inner.schema()
+    }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+}
+
+/// Reads a text file whose fields sit at fixed character offsets, as laid
+/// out by [`ReadOptions::with_fixed_width`]. Fields are trimmed and blank
+/// fields are null.
+impl Read for &'_ Reader<'_, PhantomFixedWidthReader> {
+    type Metadata = Vec<(String, usize, usize)>;
+    fn read(&self, file: &Path) -> Result<LazyFrame, ReadError> {
+        let fields = self.metadata();
+        let mut schema = self.schema()?;
+        if self.options.infer_schema().is_some() {
+            let names = fields.iter().map(|(name, _, _)| name).collect::<Vec<_>>();
+            schema = schema::partial(&schema, &names)?;
+        }
+        let layout = schema
+            .iter()
+            .map(
+                |(name, dtype)| match fields.iter().find(|(field, _, _)| field == name.as_str()) {
+                    Some((_, start, width)) => Ok(Some((*start, *width))),
+                    None if dtype == &DataType::Null => Ok(None),
+                    None => Err(format!("No fixed width field for column '{name}'")),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        let bytes = std::fs::read(file)?;
+        let text = if self.inner.enforce_utf8() {
+            std::borrow::Cow::Borrowed(
+                std::str::from_utf8(&bytes)
+                    .map_err(|e| format!("'{}' is not valid utf-8: {e}", file.display()))?,
+            )
+        } else {
+            String::from_utf8_lossy(&bytes)
+        };
+        let skip = self.inner.skip_lines() + usize::from(self.inner.has_header());
+        let rows = text
+            .lines()
+            .skip(skip)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let chars = line.chars().collect::<Vec<_>>();
+                Ok::<_, Infallible>(
+                    layout
+                        .iter()
+                        .map(|field| {
+                            let Some((start, width)) = field else {
+                                return calamine::Data::Empty;
+                            };
+                            let end = (start + width).min(chars.len());
+                            let value = chars
+                                .get(*start..end)
+                                .map(|c| c.iter().collect::<String>())
+                                .unwrap_or_default();
+                            match value.trim() {
+                                "" => calamine::Data::Empty,
+                                value => calamine::Data::String(value.to_string()),
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            });
+        // cells are located by the character offset their field starts at
+        let mut origin = CellOrigin::new(file.display().to_string(), (skip as u32, 0));
+        origin.columns = Some(
+            layout
+                .iter()
+                .map(|field| field.map_or(0, |(start, _)| start as u32))
+                .collect(),
+        );
+        Ok(build_frame(rows, &schema, &self.options, &origin)?.lazy())
+    }
+
+    fn metadata(&self) -> Self::Metadata {
+        if let Some(fields) = self.options.fixed_width() {
+            fields.to_vec()
+        } else {
+            panic!("No field layout for FixedWidthReader");
+        }
+    }
+
+    fn raw_schema(&self) -> &[(String, String)] {
+        self.inner.schema()
+    }
+
+    fn options(&self) -> &ReadOptions {
+        &self.options
+    }
 }
 
 // pub trait Reader {
@@ -349,3 +2861,365 @@ impl Read for &'_ Reader<'_, PhantomSheetRangeReader> {
 //     fn read_excel_table(&self) -> Result<LazyFrame, Box<dyn std::error::Error>>;
 //     fn read_excel_sheet_range(&self, sheet: &str, start: (usize, usize), end: (usize, usize)) -> Result<LazyFrame, Box<dyn std::error::Error>>;
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses csv bytes the way the text reader does, quoting with `'`.
+    fn read_single_quoted(csv: &[u8]) -> DataFrame {
+        CsvReadOptions::default()
+            .with_has_header(true)
+            .with_parse_options(CsvParseOptions::default().with_quote_char(Some(b'\'')))
+            .into_reader_with_file_handle(Cursor::new(csv.to_vec()))
+            .finish()
+            .unwrap()
+    }
+
+    #[test]
+    fn single_quoted_fields_keep_separators_and_doubled_quotes() {
+        let df = read_single_quoted(b"id,name\n1,'Smith, Jane'\n2,'it''s'\n3,plain\n");
+        let names = df.column("name").unwrap().str().unwrap();
+        assert_eq!(names.get(0), Some("Smith, Jane"));
+        assert_eq!(names.get(1), Some("it's"));
+        assert_eq!(names.get(2), Some("plain"));
+        assert_eq!(df.width(), 2);
+    }
+
+    /// Hands out its bytes a few at a time, to split sequences across reads.
+    struct Chunked<'a>(&'a [u8], usize);
+
+    impl std::io::Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.0.len().min(self.1).min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn count_invalid_utf8_counts_each_bad_sequence() {
+        assert_eq!(count_invalid_utf8(&b"plain text"[..]).unwrap(), 0);
+        assert_eq!(count_invalid_utf8(&b"a\xffb\xfe"[..]).unwrap(), 2);
+        // a truncated sequence at the end is one replacement
+        assert_eq!(count_invalid_utf8(&b"ab\xe2\x82"[..]).unwrap(), 1);
+    }
+
+    #[test]
+    fn count_invalid_utf8_joins_sequences_split_across_reads() {
+        let text = "caf\u{e9} \u{20ac}5".as_bytes();
+        assert_eq!(count_invalid_utf8(Chunked(text, 1)).unwrap(), 0);
+        assert_eq!(count_invalid_utf8(Chunked(b"\xe2\x82x\xff", 1)).unwrap(), 2);
+    }
+
+    #[test]
+    fn select_cells_picks_absolute_columns() {
+        use calamine::Data;
+        // a used range row starting at column B
+        let row = [Data::String("b".into()), Data::Int(3), Data::Empty];
+        assert_eq!(
+            select_cells(&row, &[2, 1, 0, 9], 1),
+            [
+                Data::Int(3),
+                Data::String("b".into()),
+                Data::Empty,
+                Data::Empty
+            ]
+        );
+    }
+
+    /// A reader of nothing but its declared schema.
+    struct Declared(Vec<(String, String)>, ReadOptions);
+
+    impl Declared {
+        fn new(columns: &[(&str, &str)]) -> Self {
+            let columns = columns
+                .iter()
+                .map(|(name, type_str)| (name.to_string(), type_str.to_string()))
+                .collect();
+            Self(columns, ReadOptions::default())
+        }
+    }
+
+    impl Read for Declared {
+        type Metadata = ();
+        fn read(&self, _file: &Path) -> Result<LazyFrame, ReadError> {
+            unreachable!("only the schema is resolved")
+        }
+        fn metadata(&self) -> Self::Metadata {}
+        fn raw_schema(&self) -> &[(String, String)] {
+            &self.0
+        }
+        fn options(&self) -> &ReadOptions {
+            &self.1
+        }
+    }
+
+    #[test]
+    fn unknown_types_are_an_error_naming_the_column() {
+        let error = Declared::new(&[("id", "int"), ("amount", "money")])
+            .schema()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            ReadError::UnknownType { column, type_name } if column == "amount" && type_name == "money"
+        ));
+        assert!(error.to_string().contains("Column 'amount'"));
+    }
+
+    #[test]
+    fn duplicate_columns_are_an_error_naming_them() {
+        let error = Declared::new(&[("id", "int"), ("name", "str"), ("id", "int")])
+            .schema()
+            .unwrap_err();
+        assert!(matches!(&error, ReadError::DuplicateColumns(names) if names == &["id"]));
+        assert!(
+            Declared::new(&[("id", "int"), ("name", "str")])
+                .schema()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn jagged_rows_are_an_error_at_the_first_missing_cell() {
+        use calamine::Data;
+        let schema = [("a", DataType::Int64), ("b", DataType::String)]
+            .into_iter()
+            .map(|(name, dtype)| (PlSmallStr::from(name), dtype))
+            .collect::<Schema>();
+        let rows = vec![
+            vec![Data::Int(1), Data::String("x".into())],
+            vec![Data::Int(2)],
+        ];
+        let origin = CellOrigin::new("book.xlsx!Sheet1".into(), (1, 0));
+        let error = build_frame(
+            rows.into_iter().map(Ok::<_, Infallible>),
+            &schema,
+            &ReadOptions::default(),
+            &origin,
+        )
+        .unwrap_err();
+        let ReadError::AtCell {
+            row,
+            column,
+            source,
+            ..
+        } = error
+        else {
+            panic!("the error is not located at a cell");
+        };
+        // one-based: the short row is sheet row 3, its missing cell is in B
+        assert_eq!((row, column), (3, 2));
+        assert!(matches!(
+            *source,
+            ReadError::ColumnCountMismatch {
+                expected: 2,
+                found: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn trim_strips_the_chosen_ends_of_strings_only() {
+        let df = polars_core::df!("s" => [" a ", "b"], "n" => [1, 2]).unwrap();
+        let trimmed = |trim| {
+            let df = trim_strings(df.clone().lazy(), trim)
+                .unwrap()
+                .collect()
+                .unwrap();
+            let s = df.column("s").unwrap().str().unwrap();
+            s.into_iter()
+                .flatten()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(trimmed(Trim::None), [" a ", "b"]);
+        assert_eq!(trimmed(Trim::Both), ["a", "b"]);
+        assert_eq!(trimmed(Trim::Start), ["a ", "b"]);
+        assert_eq!(trimmed(Trim::End), [" a", "b"]);
+        let untouched = trim_strings(df.clone().lazy(), Trim::Both)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let n = untouched.column("n").unwrap().i32().unwrap();
+        assert_eq!(n.into_iter().collect::<Vec<_>>(), [Some(1), Some(2)]);
+    }
+
+    /// An xlsx workbook with one sheet, `Sheet1`, whose `<worksheet>`
+    /// element holds `worksheet`, and the extra `parts` given by path.
+    pub(crate) fn xlsx_bytes(worksheet: &str, parts: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::Write as _;
+        const RELS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+        const MAIN: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+        let workbook = format!(
+            r#"<workbook xmlns="{MAIN}" xmlns:r="{RELS}"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#
+        );
+        let workbook_rels = format!(
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="{RELS}/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#
+        );
+        let root_rels = format!(
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="{RELS}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#
+        );
+        let content_types = r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+        let sheet =
+            format!(r#"<worksheet xmlns="{MAIN}" xmlns:r="{RELS}">{worksheet}</worksheet>"#);
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let base = [
+            ("[Content_Types].xml", content_types),
+            ("_rels/.rels", root_rels.as_str()),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", workbook_rels.as_str()),
+            ("xl/worksheets/sheet1.xml", sheet.as_str()),
+        ];
+        for (path, xml) in base.iter().chain(parts) {
+            zip.start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// Writes `bytes` to a file named `name` in the temp directory.
+    pub(crate) fn temp_file(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("qa-read-{}-{name}", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn empty_ranges_read_as_empty_frames_with_the_schema() {
+        let schema = [
+            ("a", DataType::Int64),
+            ("b", DataType::String),
+            ("gone", DataType::Null),
+        ]
+        .into_iter()
+        .map(|(name, dtype)| (PlSmallStr::from(name), dtype))
+        .collect::<Schema>();
+        let empty = calamine::Range::<calamine::Data>::empty();
+        for header in [true, false] {
+            let df = range_to_frame(
+                &empty,
+                &schema,
+                &ReadOptions::default(),
+                header,
+                "book.xlsx!Empty".into(),
+            )
+            .unwrap();
+            assert_eq!(df.height(), 0);
+            let names = df.get_column_names();
+            assert_eq!(
+                names.iter().map(|n| n.as_str()).collect::<Vec<_>>(),
+                ["a", "b"]
+            );
+        }
+        let df = empty_frame(&schema).collect().unwrap();
+        assert_eq!(df.height(), 0);
+        assert_eq!(df.dtypes(), [DataType::Int64, DataType::String]);
+    }
+
+    #[test]
+    fn missing_sheets_and_tables_are_not_found() {
+        let bytes = xlsx_bytes(
+            r#"<sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData>"#,
+            &[],
+        );
+        let mut wb = Xlsx::new(Cursor::new(bytes)).unwrap();
+        assert!(sheet_range(&mut wb, "Sheet1").is_ok());
+        assert!(matches!(
+            sheet_range(&mut wb, "Totals"),
+            Err(ReadError::NotFound { what: "sheet", name }) if name == "Totals"
+        ));
+        wb.load_tables().unwrap();
+        assert!(matches!(
+            table_by_name(&mut wb, "Items"),
+            Err(ReadError::NotFound { what: "table", name }) if name == "Items"
+        ));
+    }
+
+    #[test]
+    fn gzip_round_trips_every_member() {
+        use std::io::Write as _;
+        let gzip = |bytes: &[u8]| {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        };
+        let csv = b"id,name\n1,a\n2,b\n";
+        assert_eq!(gunzip(&gzip(csv)[..]).unwrap(), csv);
+        // concatenated members, as appending to a .gz file makes
+        let mut joined = gzip(b"id,name\n1,a\n");
+        joined.extend(gzip(b"2,b\n"));
+        assert_eq!(gunzip(&joined[..]).unwrap(), csv);
+        assert!(gunzip(&csv[..]).is_err());
+    }
+
+    #[test]
+    fn xlsb_files_are_read_as_binary_workbooks() {
+        let bytes = xlsx_bytes(
+            r#"<sheetData><row r="1"><c r="A1"><v>7</v></c></row></sheetData>"#,
+            &[],
+        );
+        let xlsx = temp_file("dispatch.xlsx", &bytes);
+        let range = worksheet_range(None, &xlsx, "Sheet1").unwrap();
+        assert_eq!(range.get_value((0, 0)), Some(&calamine::Data::Float(7.0)));
+        // the same xml parts lack the binary parts an .xlsb workbook needs
+        let xlsb = temp_file("dispatch.xlsb", &bytes);
+        assert!(worksheet_range(None, &xlsb, "Sheet1").is_err());
+        let error = with_xlsx(None, &xlsb, "tables", |_| Ok(())).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("'.xlsb' workbooks have no tables")
+        );
+    }
+
+    #[test]
+    fn linked_cells_read_their_targets_below_the_header() {
+        use calamine::Data;
+        let cell =
+            |r: &str, text: &str| format!(r#"<c r="{r}" t="inlineStr"><is><t>{text}</t></is></c>"#);
+        let rows = [
+            ("1", "name", "site"),
+            ("2", "a", "Home"),
+            ("3", "b", "Docs"),
+            ("4", "c", "none"),
+        ]
+        .iter()
+        .map(|(r, a, b)| {
+            format!(
+                r#"<row r="{r}">{}{}</row>"#,
+                cell(&format!("A{r}"), a),
+                cell(&format!("B{r}"), b)
+            )
+        })
+        .collect::<String>();
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/" TargetMode="External"/></Relationships>"#;
+        let bytes = xlsx_bytes(
+            &format!(
+                r#"<sheetData>{rows}</sheetData><hyperlinks><hyperlink ref="B2" r:id="rId1"/><hyperlink ref="B3" location="Sheet2!A1"/></hyperlinks>"#
+            ),
+            &[("xl/worksheets/_rels/sheet1.xml.rels", rels)],
+        );
+        let path = temp_file("linked.xlsx", &bytes);
+        let mut range = sheet_range(&mut Xlsx::new(Cursor::new(bytes)).unwrap(), "Sheet1").unwrap();
+        let xml = SheetXml::open(&path, "Sheet1").unwrap();
+        link_cells(&mut range, 1, 1, &xml).unwrap();
+        let column = range.rows().map(|row| row[1].clone()).collect::<Vec<_>>();
+        assert_eq!(
+            column,
+            [
+                Data::String("site".into()),
+                Data::String("https://example.com/".into()),
+                Data::String("Sheet2!A1".into()),
+                Data::Empty
+            ]
+        );
+        // other columns keep their text
+        assert_eq!(range.get_value((1, 0)), Some(&Data::String("a".into())));
+        assert!(sheet_xml(Path::new("book.xls"), "Sheet1", "hyperlinks").is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,419 @@
+use crate::options::{BinaryEncoding, InvalidValuePolicy, ReadOptions};
+use polars::prelude::{DataTypeExpr, Expr, NULL, PlSmallStr, Schema, lit, when};
+use polars_core::prelude::{AnyValue, DataType};
+use std::borrow::Cow;
+
+pub fn is_numeric(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::UInt128
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::Int128
+            | DataType::Float32
+            | DataType::Float64
+    )
+}
+
+pub fn is_integer(dtype: &DataType) -> bool {
+    is_numeric(dtype) && !matches!(dtype, DataType::Float32 | DataType::Float64)
+}
+
+/// Whether numeric csv columns must be read as text and cleaned before they
+/// are cast, because polars can't parse them directly.
+pub fn cleans_numeric_text(options: &ReadOptions) -> bool {
+    options.strip_currency_symbols()
+        || options.strip_percent_signs()
+        || options.strip_plus_signs()
+        || options.decimal_separator() != '.'
+        || options.thousands_separator().is_some()
+}
+
+/// The `(decimal, grouping)` separators of a known locale such as `de_DE`.
+pub fn locale_separators(locale: &str) -> Option<(char, Option<char>)> {
+    let separators =
+        match locale.replace('-', "_").as_str() {
+            "en_US" | "en_GB" | "en_CA" | "en_AU" | "ja_JP" | "zh_CN" | "ko_KR" | "en_IN" => {
+                ('.', Some(','))
+            }
+            "de_DE" | "de_AT" | "es_ES" | "it_IT" | "nl_NL" | "pt_BR" | "da_DK" | "id_ID"
+            | "tr_TR" => (',', Some('.')),
+            "fr_FR" | "fr_CA" | "sv_SE" | "nb_NO" | "fi_FI" | "pl_PL" | "ru_RU" | "cs_CZ"
+            | "pt_PT" => (',', Some('\u{a0}')),
+            "de_CH" | "fr_CH" | "it_CH" => ('.', Some('\'')),
+            _ => return None,
+        };
+    Some(separators)
+}
+
+/// The schema handed to the csv reader: numeric columns are read as strings
+/// when they need cleaning first.
+pub fn text_schema(schema: &Schema, options: &ReadOptions) -> Schema {
+    schema
+        .iter()
+        .map(|(name, dtype)| {
+            if reads_as_text(name, dtype, options) {
+                (name.clone(), DataType::String)
+            } else {
+                (name.clone(), dtype.clone())
+            }
+        })
+        .collect()
+}
+
+/// Selects the non-`Null` columns of a frame read with [`text_schema`],
+/// cleaning and casting the numeric ones.
+pub fn select_columns(schema: &Schema, options: &ReadOptions) -> Vec<Expr> {
+    schema
+        .iter()
+        .filter(|(_, dtype)| *dtype != &DataType::Null)
+        .map(|(name, dtype)| {
+            if reads_as_text(name, dtype, options) {
+                from_text(name, dtype, options)
+            } else {
+                Expr::Column(name.clone())
+            }
+        })
+        .collect()
+}
+
+/// Selects the non-`Null` columns of a frame whose storage carries its own
+/// types, casting each to the schema type.
+pub fn cast_columns(schema: &Schema, options: &ReadOptions) -> Vec<Expr> {
+    schema
+        .iter()
+        .filter(|(_, dtype)| *dtype != &DataType::Null)
+        .map(|(name, dtype)| cast_column(name, dtype, options))
+        .collect()
+}
+
+/// Casts a column to `dtype`, strictly when invalid values are errors.
+pub fn cast_column(name: &PlSmallStr, dtype: &DataType, options: &ReadOptions) -> Expr {
+    let column = Expr::Column(name.clone());
+    match options.invalid_values() {
+        InvalidValuePolicy::Null => column.cast(DataTypeExpr::from(dtype.clone())),
+        InvalidValuePolicy::Error => column.strict_cast(DataTypeExpr::from(dtype.clone())),
+    }
+}
+
+/// Selects the columns of a frame read with inference for the columns the
+/// schema doesn't declare: declared columns as [`select_columns`] would, the
+/// others as inferred, in file order.
+pub fn select_partial(file: &Schema, schema: &Schema, options: &ReadOptions) -> Vec<Expr> {
+    file.iter_names()
+        .filter_map(|name| match schema.get(name) {
+            None => Some(Expr::Column(name.clone())),
+            Some(DataType::Null) => None,
+            Some(dtype) if reads_as_text(name, dtype, options) => {
+                Some(from_text(name, dtype, options))
+            }
+            Some(_) => Some(Expr::Column(name.clone())),
+        })
+        .collect()
+}
+
+/// The schema overwrite handed to the csv reader when inferring the columns
+/// the schema doesn't declare: [`text_schema`], with removed columns read as
+/// strings.
+pub fn overwrite_schema(schema: &Schema, options: &ReadOptions) -> Schema {
+    text_schema(schema, options)
+        .iter()
+        .map(|(name, dtype)| match dtype {
+            DataType::Null => (name.clone(), DataType::String),
+            _ => (name.clone(), dtype.clone()),
+        })
+        .collect()
+}
+
+/// Whether csv column `name` of `dtype` is read as text and converted by
+/// [`from_text`]: cleaned numbers, binary columns, which the csv reader
+/// can't parse, and columns with null tokens of their own, which the csv
+/// reader takes one of per column.
+fn reads_as_text(name: &PlSmallStr, dtype: &DataType, options: &ReadOptions) -> bool {
+    (cleans_numeric_text(options) && is_numeric(dtype))
+        || dtype == &DataType::Binary
+        || column_null_tokens(name, options).next().is_some()
+}
+
+/// The null tokens set for column `name` alone.
+fn column_null_tokens<'o>(
+    name: &'o PlSmallStr,
+    options: &'o ReadOptions,
+) -> impl Iterator<Item = &'o str> {
+    options
+        .column_null_values()
+        .iter()
+        .filter(move |(column, _)| column == name.as_str())
+        .map(|(_, token)| token.as_str())
+}
+
+/// Casts a string column to `dtype`, nulling the column's own null tokens,
+/// cleaning numeric text and decoding binary text first.
+pub fn from_text(name: &PlSmallStr, dtype: &DataType, options: &ReadOptions) -> Expr {
+    let mut expr = Expr::Column(name.clone());
+    let is_token = column_null_tokens(name, options)
+        .map(|token| expr.clone().eq(lit(token.to_string())))
+        .reduce(|any, is_token| any.or(is_token));
+    if let Some(is_token) = is_token {
+        expr = when(is_token).then(lit(NULL)).otherwise(expr);
+    }
+    if dtype == &DataType::Binary {
+        let strict = options.invalid_values() == InvalidValuePolicy::Error;
+        let bytes = expr.cast(DataTypeExpr::from(DataType::Binary));
+        return match options.binary_encoding() {
+            BinaryEncoding::Raw => bytes,
+            BinaryEncoding::Hex => bytes.binary().hex_decode(strict),
+            BinaryEncoding::Base64 => bytes.binary().base64_decode(strict),
+        };
+    }
+    if is_numeric(dtype) && options.strip_currency_symbols() {
+        for symbol in options.currency_symbols().chars() {
+            expr = expr
+                .str()
+                .replace_all(lit(symbol.to_string()), lit(""), true);
+        }
+        expr = expr.str().strip_chars(lit(NULL));
+    }
+    if is_numeric(dtype) && options.strip_percent_signs() {
+        expr = expr
+            .str()
+            .replace_all(lit("%"), lit(""), true)
+            .str()
+            .strip_chars(lit(NULL));
+    }
+    if is_numeric(dtype) && options.strip_plus_signs() {
+        expr = expr
+            .str()
+            .strip_chars_start(lit(NULL))
+            .str()
+            .strip_prefix(lit("+"));
+    }
+    if is_numeric(dtype) {
+        if let Some(grouping) = options.thousands_separator() {
+            expr = expr
+                .str()
+                .replace_all(lit(grouping.to_string()), lit(""), true);
+        }
+        if options.decimal_separator() != '.' {
+            expr = expr.str().replace_all(
+                lit(options.decimal_separator().to_string()),
+                lit("."),
+                true,
+            );
+        }
+    }
+    match options.invalid_values() {
+        InvalidValuePolicy::Null => expr.cast(DataTypeExpr::from(dtype.clone())),
+        InvalidValuePolicy::Error => expr.strict_cast(DataTypeExpr::from(dtype.clone())),
+    }
+}
+
+/// Cleans numeric text from an Excel string cell the same way [`from_text`]
+/// does for csv columns.
+pub fn numeric_text<'s>(value: &'s str, options: &ReadOptions) -> Cow<'s, str> {
+    let mut value = Cow::Borrowed(value.trim());
+    if options.strip_currency_symbols() {
+        value = remove_chars(value, |c| options.currency_symbols().contains(c));
+    }
+    if options.strip_percent_signs() {
+        value = remove_chars(value, |c| c == '%');
+    }
+    if options.strip_plus_signs()
+        && let Some(unsigned) = value.strip_prefix('+')
+    {
+        value = Cow::Owned(unsigned.to_string());
+    }
+    let decimal = options.decimal_separator();
+    // text numbers typed in Excel commonly group digits, with ',' unless the
+    // decimal separator is ','
+    let grouping = options
+        .thousands_separator()
+        .or((decimal != ',').then_some(','));
+    if decimal == '.' && grouping.is_none_or(|g| !value.contains(g)) {
+        return value;
+    }
+    Cow::Owned(
+        value
+            .chars()
+            .filter(|c| Some(*c) != grouping)
+            .map(|c| if c == decimal { '.' } else { c })
+            .collect(),
+    )
+}
+
+/// Removes every character `remove` matches, and the whitespace left at the
+/// ends.
+fn remove_chars(value: Cow<'_, str>, remove: impl Fn(char) -> bool) -> Cow<'_, str> {
+    if !value.chars().any(&remove) {
+        return value;
+    }
+    let kept = value.chars().filter(|c| !remove(*c)).collect::<String>();
+    Cow::Owned(kept.trim().to_string())
+}
+
+/// Parses cleaned numeric text as `dtype`, `None` when it doesn't parse.
+pub fn parse_number(value: &str, dtype: &DataType) -> Option<AnyValue<'static>> {
+    match dtype {
+        DataType::UInt8 => value.parse().ok().map(AnyValue::UInt8),
+        DataType::UInt16 => value.parse().ok().map(AnyValue::UInt16),
+        DataType::UInt32 => value.parse().ok().map(AnyValue::UInt32),
+        DataType::UInt64 => value.parse().ok().map(AnyValue::UInt64),
+        DataType::UInt128 => value.parse().ok().map(AnyValue::UInt128),
+        DataType::Int8 => value.parse().ok().map(AnyValue::Int8),
+        DataType::Int16 => value.parse().ok().map(AnyValue::Int16),
+        DataType::Int32 => value.parse().ok().map(AnyValue::Int32),
+        DataType::Int64 => value.parse().ok().map(AnyValue::Int64),
+        DataType::Int128 => value.parse().ok().map(AnyValue::Int128),
+        DataType::Float32 => value.parse().ok().map(AnyValue::Float32),
+        DataType::Float64 => value.parse().ok().map(AnyValue::Float64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars_core::prelude::Column;
+
+    #[test]
+    fn numeric_text_drops_grouping_by_default() {
+        let options = ReadOptions::default();
+        assert_eq!(numeric_text(" 12 ", &options), "12");
+        assert_eq!(numeric_text("1,234.5", &options), "1234.5");
+    }
+
+    #[test]
+    fn numeric_text_follows_the_locale() {
+        let options = ReadOptions::default().with_locale("de_DE");
+        assert_eq!(numeric_text("1.234,5", &options), "1234.5");
+        let options = ReadOptions::default().with_locale("fr-FR");
+        assert_eq!(numeric_text("1\u{a0}234,5", &options), "1234.5");
+    }
+
+    #[test]
+    fn numeric_text_strips_plus_signs_of_integers() {
+        let options = ReadOptions::default().with_strip_plus_signs(true);
+        assert_eq!(numeric_text("+1234", &options), "1234");
+        assert_eq!(
+            parse_number(&numeric_text(" +7", &options), &DataType::Int32),
+            Some(AnyValue::Int32(7))
+        );
+        assert_eq!(
+            parse_number(&numeric_text("-7", &options), &DataType::Int64),
+            Some(AnyValue::Int64(-7))
+        );
+    }
+
+    #[test]
+    fn parse_number_parses_cleaned_text() {
+        assert_eq!(
+            parse_number("1234.5", &DataType::Float64),
+            Some(AnyValue::Float64(1234.5))
+        );
+        assert_eq!(parse_number("300", &DataType::UInt8), None);
+        assert_eq!(parse_number("7", &DataType::String), None);
+    }
+
+    #[test]
+    fn numeric_text_strips_currency_symbols() {
+        let options = ReadOptions::default().with_strip_currency_symbols(true);
+        assert_eq!(numeric_text("$ 12.50", &options), "12.50");
+        assert_eq!(numeric_text("12.50\u{20ac}", &options), "12.50");
+        let options = ReadOptions::default();
+        assert_eq!(numeric_text("$12.50", &options), "$12.50");
+    }
+
+    /// Reads string column `values` as `dtype` the way the csv reader does.
+    fn read_text(values: &[&str], dtype: DataType, options: &ReadOptions) -> Column {
+        use polars::prelude::IntoLazy;
+        let schema = Schema::from_iter([(PlSmallStr::from("value"), dtype)]);
+        polars_core::df!("value" => values)
+            .unwrap()
+            .lazy()
+            .select(select_columns(&schema, options))
+            .collect()
+            .unwrap()
+            .column("value")
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn currency_symbols_are_removed_wherever_they_stand() {
+        let options = ReadOptions::default().with_strip_currency_symbols(true);
+        assert_eq!(numeric_text("-$12.50", &options), "-12.50");
+        assert_eq!(numeric_text("\u{20ac}-3", &options), "-3");
+        let column = read_text(
+            &["-$12.50", "$ 7", "5\u{20ac}"],
+            DataType::Float64,
+            &options,
+        );
+        let values = column.f64().unwrap();
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            [Some(-12.5), Some(7.0), Some(5.0)]
+        );
+    }
+
+    #[test]
+    fn every_thousands_separator_is_removed() {
+        let options = ReadOptions::default().with_locale("de_DE");
+        assert_eq!(numeric_text("1.234.567,5", &options), "1234567.5");
+        let column = read_text(&["1.234.567", "12"], DataType::Int64, &options);
+        let values = column.i64().unwrap();
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            [Some(1234567), Some(12)]
+        );
+    }
+
+    #[test]
+    fn percent_signs_are_removed_without_scaling() {
+        let options = ReadOptions::default().with_strip_percent_signs(true);
+        assert_eq!(numeric_text("12.5%", &options), "12.5");
+        assert_eq!(numeric_text("12.5%", &ReadOptions::default()), "12.5%");
+        let column = read_text(&["12.5 %", "-3%"], DataType::Float64, &options);
+        let values = column.f64().unwrap();
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            [Some(12.5), Some(-3.0)]
+        );
+    }
+
+    #[test]
+    fn column_null_tokens_null_their_column_only() {
+        use polars::prelude::IntoLazy;
+        let schema = Schema::from_iter([
+            (PlSmallStr::from("amount"), DataType::Int64),
+            (PlSmallStr::from("note"), DataType::String),
+        ]);
+        let options = ReadOptions::default()
+            .with_column_null_value("amount", "N/A")
+            .with_column_null_value("amount", "-");
+        let df = polars_core::df!(
+            "amount" => ["N/A", "-", "3"],
+            "note" => ["N/A", "-", "x"],
+        )
+        .unwrap()
+        .lazy()
+        .select(select_columns(&schema, &options))
+        .collect()
+        .unwrap();
+        let amounts = df.column("amount").unwrap().i64().unwrap();
+        assert_eq!(
+            amounts.into_iter().collect::<Vec<_>>(),
+            [None, None, Some(3)]
+        );
+        let notes = df.column("note").unwrap().str().unwrap();
+        assert_eq!(
+            notes.into_iter().collect::<Vec<_>>(),
+            [Some("N/A"), Some("-"), Some("x")]
+        );
+    }
+}
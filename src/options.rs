@@ -0,0 +1,1136 @@
+use crate::cache::ReadCache;
+use crate::error::ReadError;
+use crate::normalize;
+use crate::workbook::Workbook;
+use polars::prelude::Expr;
+use polars_core::prelude::{AnyValue, DataType};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What to do when a row has fewer cells than the schema declares columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColumnCountPolicy {
+    /// Fail with [`ReadError::ColumnCountMismatch`](crate::ReadError::ColumnCountMismatch).
+    #[default]
+    Error,
+    /// Fill the missing trailing columns with nulls.
+    FillNull,
+}
+
+/// What to do with a value that can't be converted to its declared type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidValuePolicy {
+    /// Read the value as null.
+    #[default]
+    Null,
+    /// Fail the read.
+    Error,
+}
+
+/// Which duplicate rows are dropped on read. Off by default since duplicates
+/// are meaningful to most comparisons.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dedup {
+    #[default]
+    Off,
+    /// Rows equal in every column.
+    Rows,
+    /// Rows equal in the key columns.
+    Keys,
+}
+
+/// What happens to control characters in string cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlChars {
+    #[default]
+    Keep,
+    Remove,
+    Replace(char),
+}
+
+/// What happens to line breaks inside string values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Newlines {
+    #[default]
+    Keep,
+    /// Turns `\r\n` and lone `\r` into `\n`.
+    Normalize,
+    /// Removes every line break.
+    Remove,
+}
+
+/// Which ends of string values whitespace is trimmed from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Trim {
+    #[default]
+    None,
+    Both,
+    Start,
+    End,
+}
+
+/// Which characters count as control characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlCharSet {
+    /// Every control character except tab, line feed and carriage return.
+    #[default]
+    ExceptWhitespace,
+    /// Every control character.
+    All,
+}
+
+impl ControlCharSet {
+    /// The set as a regex character class.
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            ControlCharSet::ExceptWhitespace => r"[\p{Cc}&&[^\t\n\r]]",
+            ControlCharSet::All => r"\p{Cc}",
+        }
+    }
+}
+
+/// How text is turned into the bytes of a `binary` column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// The UTF-8 bytes of the text itself.
+    #[default]
+    Raw,
+    /// Hexadecimal digits, two per byte.
+    Hex,
+    /// Standard base64, with padding.
+    Base64,
+}
+
+/// The epoch Excel counts serial dates from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateSystem {
+    /// Serial 1 is 1900-01-01, the Windows default.
+    #[default]
+    Excel1900,
+    /// Serial 0 is 1904-01-01, used by workbooks from older Mac Excel.
+    Excel1904,
+}
+
+/// Reshapes a wide frame, one column per period or category, into a long
+/// one with a variable and a value column.
+#[derive(Debug, Clone, Default)]
+pub struct Unpivot {
+    /// Columns identifying a row, kept as they are.
+    pub index: Vec<String>,
+    /// Columns melted into variable/value pairs.
+    pub on: Vec<String>,
+    /// Name of the variable column, `variable` by default.
+    pub variable_name: Option<String>,
+    /// Name of the value column, `value` by default.
+    pub value_name: Option<String>,
+    /// Renames melted column names to variable values, e.g. `Jan` to
+    /// `2024-01`. Unlisted columns keep their name.
+    pub variables: Vec<(String, String)>,
+}
+
+/// Text read as `true` in boolean columns unless configured otherwise: flag
+/// spellings and Excel's localized `TRUE` in common languages.
+pub const DEFAULT_TRUE_TOKENS: &[&str] = &[
+    "true",
+    "yes",
+    "y",
+    "1",
+    "wahr",
+    "vrai",
+    "verdadero",
+    "vero",
+    "waar",
+    "verdadeiro",
+    "sant",
+    "prawda",
+    "igaz",
+    "pravda",
+    "tosi",
+];
+
+/// Text read as `false` in boolean columns unless configured otherwise: flag
+/// spellings and Excel's localized `FALSE` in common languages.
+pub const DEFAULT_FALSE_TOKENS: &[&str] = &[
+    "false", "no", "n", "0", "falsch", "faux", "falso", "onwaar", "falsk", "fałsz", "hamis",
+    "nepravda", "epätosi",
+];
+
+/// Rewrites a column's expression, e.g. to normalize product codes.
+pub type ColumnTransform = Arc<dyn Fn(Expr) -> Expr + Send + Sync>;
+
+#[derive(Clone, Default)]
+struct Transforms(Vec<(String, ColumnTransform)>);
+
+impl fmt::Debug for Transforms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(column, _)| column))
+            .finish()
+    }
+}
+
+/// Reader settings that are not carried by the `Comparable` itself.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    sheet_columns: Option<Vec<String>>,
+    column_count_policy: ColumnCountPolicy,
+    transpose: bool,
+    null_sentinels: Vec<(DataType, AnyValue<'static>)>,
+    key_columns: Vec<String>,
+    flag_formula_columns: bool,
+    reject_formula_keys: bool,
+    eager_threshold: Option<u64>,
+    positional_columns: bool,
+    source_column: Option<String>,
+    stream_cells: bool,
+    include_columns: Option<Vec<String>>,
+    exclude_columns: Vec<String>,
+    invalid_values: InvalidValuePolicy,
+    strip_currency_symbols: bool,
+    currency_symbols: Option<String>,
+    dedup: Dedup,
+    schema_dictionary: bool,
+    locale: Option<String>,
+    decimal_separator: Option<char>,
+    thousands_separator: Option<char>,
+    control_chars: ControlChars,
+    control_char_set: ControlCharSet,
+    max_string_len: Option<usize>,
+    truncate_long_strings: bool,
+    unpivot: Option<Unpivot>,
+    named_cells: Vec<(String, String)>,
+    range_header: bool,
+    reject_overlong_fixed: bool,
+    transforms: Transforms,
+    strict_schema: bool,
+    strip_plus_signs: bool,
+    bool_tokens: Option<(Vec<String>, Vec<String>)>,
+    cache: Option<ReadCache>,
+    max_line_bytes: Option<u64>,
+    defined_name: Option<String>,
+    sheets: Vec<String>,
+    zip_member: Option<String>,
+    fixed_width: Option<Vec<(String, usize, usize)>>,
+    html_table: usize,
+    http_timeout: Option<Duration>,
+    date_format: Option<String>,
+    date_system: DateSystem,
+    infer_schema: Option<usize>,
+    match_by_name: bool,
+    check_header_names: bool,
+    n_rows: Option<usize>,
+    workbook: Option<Workbook>,
+    encoding: Option<String>,
+    binary_encoding: BinaryEncoding,
+    column_null_values: Vec<(String, String)>,
+    trim: Trim,
+    newlines: Newlines,
+    count_rows: bool,
+    skip_rows: usize,
+    range: Option<String>,
+    row_offset: usize,
+    strip_percent_signs: bool,
+    hyperlink_column: Option<String>,
+    auto_filter_range: bool,
+    skip_collapsed_columns: bool,
+}
+
+impl ReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the options for settings that can't be applied.
+    pub fn validate(&self) -> Result<(), ReadError> {
+        if let Some(locale) = &self.locale
+            && normalize::locale_separators(locale).is_none()
+        {
+            return Err(format!("Unknown locale '{locale}'").into());
+        }
+        if let Some(range) = &self.range {
+            crate::a1::range_reference(range)?;
+        }
+        if let Some(column) = &self.hyperlink_column {
+            crate::a1::column_index(column)?;
+        }
+        if let Some(encoding) = &self.encoding
+            && encoding_rs::Encoding::for_label(encoding.as_bytes()).is_none()
+        {
+            return Err(format!("Unknown encoding '{encoding}'").into());
+        }
+        Ok(())
+    }
+
+    /// Reads only the given columns of the sheet's used range, in the given
+    /// order, instead of the coordinates of the `SheetRange`. Columns are
+    /// letters (`"A"`, `"C"`) or zero-based indices (`"5"`) and are matched to
+    /// the schema positionally.
+    pub fn with_sheet_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sheet_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn sheet_columns(&self) -> Option<&[String]> {
+        self.sheet_columns.as_deref()
+    }
+
+    /// Sets how Excel rows narrower than the schema are handled.
+    pub fn with_column_count_policy(mut self, policy: ColumnCountPolicy) -> Self {
+        self.column_count_policy = policy;
+        self
+    }
+
+    pub fn column_count_policy(&self) -> ColumnCountPolicy {
+        self.column_count_policy
+    }
+
+    /// Treats the source as transposed: each source row becomes a column,
+    /// named after the schema in order, before types are applied.
+    pub fn with_transpose(mut self, transpose: bool) -> Self {
+        self.transpose = transpose;
+        self
+    }
+
+    pub fn transpose(&self) -> bool {
+        self.transpose
+    }
+
+    /// Replaces nulls in every returned column of `dtype` with `value`, for
+    /// consumers that cannot represent null. Replaced columns are listed in
+    /// the [`ReadReport`](crate::ReadReport).
+    pub fn with_null_sentinel(mut self, dtype: DataType, value: AnyValue<'static>) -> Self {
+        self.null_sentinels.retain(|(dt, _)| dt != &dtype);
+        self.null_sentinels.push((dtype, value));
+        self
+    }
+
+    pub fn null_sentinels(&self) -> &[(DataType, AnyValue<'static>)] {
+        &self.null_sentinels
+    }
+
+    /// Names the columns that identify a row in the comparison.
+    pub fn with_key_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.key_columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn key_columns(&self) -> &[String] {
+        &self.key_columns
+    }
+
+    /// Lists formula-backed Excel table columns in the
+    /// [`ReadReport`](crate::ReadReport).
+    pub fn with_flag_formula_columns(mut self, flag: bool) -> Self {
+        self.flag_formula_columns = flag;
+        self
+    }
+
+    pub fn flag_formula_columns(&self) -> bool {
+        self.flag_formula_columns
+    }
+
+    /// Fails the read when a key column is formula-backed. Only checked when
+    /// formula columns are flagged.
+    pub fn with_reject_formula_keys(mut self, reject: bool) -> Self {
+        self.reject_formula_keys = reject;
+        self
+    }
+
+    pub fn reject_formula_keys(&self) -> bool {
+        self.reject_formula_keys
+    }
+
+    /// Reads csv files smaller than `bytes` eagerly, skipping the lazy
+    /// planning overhead that dominates tiny reads. The result is the same.
+    pub fn with_eager_threshold(mut self, bytes: Option<u64>) -> Self {
+        self.eager_threshold = bytes;
+        self
+    }
+
+    pub fn eager_threshold(&self) -> Option<u64> {
+        self.eager_threshold
+    }
+
+    /// Reads csv columns strictly by position: the header line, when the
+    /// `Comparable` says there is one, is skipped rather than matched, so
+    /// duplicated or missing header names don't matter.
+    pub fn with_positional_columns(mut self, positional: bool) -> Self {
+        self.positional_columns = positional;
+        self
+    }
+
+    pub fn positional_columns(&self) -> bool {
+        self.positional_columns
+    }
+
+    /// Appends a string column called `name` holding where each row came
+    /// from: the file path, suffixed with `!sheet`, `!table` or
+    /// `!sheet/pivot` for Excel.
+    pub fn with_source_column(mut self, name: impl Into<String>) -> Self {
+        self.source_column = Some(name.into());
+        self
+    }
+
+    pub fn source_column(&self) -> Option<&str> {
+        self.source_column.as_deref()
+    }
+
+    /// Streams sheet range cells from the worksheet instead of loading the
+    /// whole sheet first, roughly halving peak memory on large sheets.
+    /// Ignored when sheet columns are selected.
+    pub fn with_stream_cells(mut self, stream: bool) -> Self {
+        self.stream_cells = stream;
+        self
+    }
+
+    pub fn stream_cells(&self) -> bool {
+        self.stream_cells
+    }
+
+    /// Keeps only these columns, in this order, in the returned frame.
+    /// Applied after the schema, so it needs no schema edits. Schema columns
+    /// left out are not read at all, as if typed `Null`: Excel readers skip
+    /// their cells and csv and typed formats project them away, unless
+    /// unpivoting, which needs every column.
+    pub fn with_include_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn include_columns(&self) -> Option<&[String]> {
+        self.include_columns.as_deref()
+    }
+
+    /// Drops these columns from the returned frame. A column may not be both
+    /// included and excluded.
+    pub fn with_exclude_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude_columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn exclude_columns(&self) -> &[String] {
+        &self.exclude_columns
+    }
+
+    /// Sets how values that don't convert to their declared type are
+    /// handled.
+    pub fn with_invalid_values(mut self, policy: InvalidValuePolicy) -> Self {
+        self.invalid_values = policy;
+        self
+    }
+
+    pub fn invalid_values(&self) -> InvalidValuePolicy {
+        self.invalid_values
+    }
+
+    /// Strips currency symbols from numeric columns before they are parsed,
+    /// wherever they stand, so `$1234.56` and `-$12` read as `1234.56` and
+    /// `-12`.
+    pub fn with_strip_currency_symbols(mut self, strip: bool) -> Self {
+        self.strip_currency_symbols = strip;
+        self
+    }
+
+    pub fn strip_currency_symbols(&self) -> bool {
+        self.strip_currency_symbols
+    }
+
+    /// Sets the characters treated as currency symbols, `$€£¥` by default.
+    pub fn with_currency_symbols(mut self, symbols: impl Into<String>) -> Self {
+        self.currency_symbols = Some(symbols.into());
+        self
+    }
+
+    pub fn currency_symbols(&self) -> &str {
+        self.currency_symbols.as_deref().unwrap_or("$€£¥")
+    }
+
+    /// Drops duplicate rows, keeping the first occurrence. The number
+    /// dropped is reported in the [`ReadReport`](crate::ReadReport).
+    pub fn with_dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn dedup(&self) -> Dedup {
+        self.dedup
+    }
+
+    /// Treats the schema as a dictionary of known columns: the file's own
+    /// header decides which columns are read, and the dictionary types them.
+    /// Excel sheet ranges take the header from their first row.
+    pub fn with_schema_dictionary(mut self, dictionary: bool) -> Self {
+        self.schema_dictionary = dictionary;
+        self
+    }
+
+    pub fn schema_dictionary(&self) -> bool {
+        self.schema_dictionary
+    }
+
+    /// Parses numeric text with the separators of a regional format such as
+    /// `de_DE` (`1.234,56`). Explicit separators take precedence.
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    pub fn with_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = Some(separator);
+        self
+    }
+
+    /// The explicit decimal separator, else the locale's, else `.`.
+    pub fn decimal_separator(&self) -> char {
+        self.decimal_separator
+            .or_else(|| self.locale_separators().map(|(decimal, _)| decimal))
+            .unwrap_or('.')
+    }
+
+    pub fn with_thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// The explicit grouping separator, else the locale's, else none.
+    pub fn thousands_separator(&self) -> Option<char> {
+        self.thousands_separator
+            .or_else(|| self.locale_separators().and_then(|(_, grouping)| grouping))
+    }
+
+    fn locale_separators(&self) -> Option<(char, Option<char>)> {
+        self.locale
+            .as_deref()
+            .and_then(normalize::locale_separators)
+    }
+
+    /// Removes or replaces control characters, such as stray vertical tabs
+    /// from pasted Excel data, in every string column. The number of affected
+    /// cells is reported in the [`ReadReport`](crate::ReadReport).
+    pub fn with_control_chars(mut self, control_chars: ControlChars) -> Self {
+        self.control_chars = control_chars;
+        self
+    }
+
+    pub fn control_chars(&self) -> ControlChars {
+        self.control_chars
+    }
+
+    pub fn with_control_char_set(mut self, set: ControlCharSet) -> Self {
+        self.control_char_set = set;
+        self
+    }
+
+    pub fn control_char_set(&self) -> ControlCharSet {
+        self.control_char_set
+    }
+
+    /// Flags string columns with values longer than `chars` characters in
+    /// the [`ReadReport`](crate::ReadReport), guarding against a stray huge
+    /// cell dominating an otherwise clean dataset.
+    pub fn with_max_string_len(mut self, chars: Option<usize>) -> Self {
+        self.max_string_len = chars;
+        self
+    }
+
+    pub fn max_string_len(&self) -> Option<usize> {
+        self.max_string_len
+    }
+
+    /// Truncates flagged values to the maximum length.
+    pub fn with_truncate_long_strings(mut self, truncate: bool) -> Self {
+        self.truncate_long_strings = truncate;
+        self
+    }
+
+    pub fn truncate_long_strings(&self) -> bool {
+        self.truncate_long_strings
+    }
+
+    /// Unpivots the returned frame from wide to long format.
+    pub fn with_unpivot(mut self, unpivot: Unpivot) -> Self {
+        self.unpivot = Some(unpivot);
+        self
+    }
+
+    pub fn unpivot(&self) -> Option<&Unpivot> {
+        self.unpivot.as_ref()
+    }
+
+    /// Reads single cells outside the main frame, such as a report date,
+    /// into [`ReadReport::named_cells`](crate::ReadReport::named_cells).
+    /// Each cell is a name and an A1 reference, `"B2"` on the sheet being
+    /// read or `"Sheet!B2"` on another one.
+    pub fn with_named_cells<I, K, V>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.named_cells = cells
+            .into_iter()
+            .map(|(name, reference)| (name.into(), reference.into()))
+            .collect();
+        self
+    }
+
+    pub fn named_cells(&self) -> &[(String, String)] {
+        &self.named_cells
+    }
+
+    /// Treats the first row of a `SheetRange` as a header. Its cells are
+    /// checked against the schema column names and the row is not read as
+    /// data.
+    pub fn with_range_header(mut self, range_header: bool) -> Self {
+        self.range_header = range_header;
+        self
+    }
+
+    pub fn range_header(&self) -> bool {
+        self.range_header
+    }
+
+    /// Fails the read when a value of a fixed length string column, such as
+    /// `str(10)`, is longer than its length instead of truncating it.
+    pub fn with_reject_overlong_fixed(mut self, reject: bool) -> Self {
+        self.reject_overlong_fixed = reject;
+        self
+    }
+
+    pub fn reject_overlong_fixed(&self) -> bool {
+        self.reject_overlong_fixed
+    }
+
+    /// Applies `transform` to `column` once it has been read and cast. A
+    /// column can have several transforms, applied in the order added.
+    pub fn with_transform<F>(mut self, column: impl Into<String>, transform: F) -> Self
+    where
+        F: Fn(Expr) -> Expr + Send + Sync + 'static,
+    {
+        self.transforms.0.push((column.into(), Arc::new(transform)));
+        self
+    }
+
+    pub fn transforms(&self) -> &[(String, ColumnTransform)] {
+        &self.transforms.0
+    }
+
+    /// Requires the file's columns to match the schema exactly, in names,
+    /// order and count, before any value is read.
+    pub fn with_strict_schema(mut self, strict: bool) -> Self {
+        self.strict_schema = strict;
+        self
+    }
+
+    pub fn strict_schema(&self) -> bool {
+        self.strict_schema
+    }
+
+    /// Accepts numbers written with an explicit sign, such as `+1234`, in
+    /// numeric columns. String columns keep their leading `+`.
+    pub fn with_strip_plus_signs(mut self, strip: bool) -> Self {
+        self.strip_plus_signs = strip;
+        self
+    }
+
+    pub fn strip_plus_signs(&self) -> bool {
+        self.strip_plus_signs
+    }
+
+    /// Replaces the text read as `true` and `false` from string cells of
+    /// boolean columns, [`DEFAULT_TRUE_TOKENS`] and [`DEFAULT_FALSE_TOKENS`]
+    /// by default. Tokens match case-insensitively; other text is invalid.
+    pub fn with_bool_tokens<T, F, S>(mut self, true_tokens: T, false_tokens: F) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        F: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.bool_tokens = Some((
+            true_tokens.into_iter().map(Into::into).collect(),
+            false_tokens.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Parses boolean text with the configured tokens.
+    pub fn parse_bool(&self, value: &str) -> Option<bool> {
+        let value = value.trim().to_lowercase();
+        let matches = |token: &str| token.to_lowercase() == value;
+        let (is_true, is_false) = match &self.bool_tokens {
+            Some((true_tokens, false_tokens)) => (
+                true_tokens.iter().any(|t| matches(t)),
+                false_tokens.iter().any(|t| matches(t)),
+            ),
+            None => (
+                DEFAULT_TRUE_TOKENS.iter().any(|t| matches(t)),
+                DEFAULT_FALSE_TOKENS.iter().any(|t| matches(t)),
+            ),
+        };
+        match (is_true, is_false) {
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Serves reads of unchanged files from `cache`. Cached reads are
+    /// collected, so the returned frames are no longer lazy over the file.
+    /// Files are told apart by a hash of their whole content, which each read
+    /// computes by reading the file through. Reads with column transforms are
+    /// never cached.
+    pub fn with_cache(mut self, cache: ReadCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&ReadCache> {
+        self.cache.as_ref()
+    }
+
+    /// Scans csv files before reading them and fails with
+    /// [`ReadError::LineTooLong`](crate::ReadError::LineTooLong) when a
+    /// record exceeds `max_line_bytes`, such as one swallowed by an
+    /// unterminated quote.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: u64) -> Self {
+        self.max_line_bytes = Some(max_line_bytes);
+        self
+    }
+
+    pub fn max_line_bytes(&self) -> Option<u64> {
+        self.max_line_bytes
+    }
+
+    /// Reads the range the workbook's defined name refers to, such as
+    /// `SalesData`, in place of the coordinates of a `SheetRange`.
+    pub fn with_defined_name(mut self, name: impl Into<String>) -> Self {
+        self.defined_name = Some(name.into());
+        self
+    }
+
+    pub fn defined_name(&self) -> Option<&str> {
+        self.defined_name.as_deref()
+    }
+
+    /// Reads the `SheetRange` bounds from each of `sheets` in turn and stacks
+    /// the results into one frame. Every sheet must fit the schema.
+    pub fn with_sheets<I, S>(mut self, sheets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sheets = sheets.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn sheets(&self) -> &[String] {
+        &self.sheets
+    }
+
+    /// The csv file to read from a `.zip` archive, needed when the archive
+    /// holds more than one file.
+    pub fn with_zip_member(mut self, member: impl Into<String>) -> Self {
+        self.zip_member = Some(member.into());
+        self
+    }
+
+    pub fn zip_member(&self) -> Option<&str> {
+        self.zip_member.as_deref()
+    }
+
+    /// Reads text files as fixed width records instead of csv. Each field is
+    /// a column name with its zero-based start and width in characters.
+    pub fn with_fixed_width<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = (S, usize, usize)>,
+        S: Into<String>,
+    {
+        self.fixed_width = Some(
+            fields
+                .into_iter()
+                .map(|(name, start, width)| (name.into(), start, width))
+                .collect(),
+        );
+        self
+    }
+
+    pub fn fixed_width(&self) -> Option<&[(String, usize, usize)]> {
+        self.fixed_width.as_deref()
+    }
+
+    /// Which `<table>` of an html page to read, counting from zero.
+    pub fn with_html_table(mut self, index: usize) -> Self {
+        self.html_table = index;
+        self
+    }
+
+    pub fn html_table(&self) -> usize {
+        self.html_table
+    }
+
+    /// How long downloading a csv file from an http(s) url may take, 30
+    /// seconds by default.
+    pub fn with_http_timeout(mut self, timeout: Duration) -> Self {
+        self.http_timeout = Some(timeout);
+        self
+    }
+
+    pub fn http_timeout(&self) -> Duration {
+        self.http_timeout.unwrap_or(Duration::from_secs(30))
+    }
+
+    /// A `chrono` format such as `%d/%m/%Y` for date text in Excel cells of
+    /// `Date` and `Datetime` columns, tried after ISO 8601.
+    pub fn with_date_format(mut self, format: impl Into<String>) -> Self {
+        self.date_format = Some(format.into());
+        self
+    }
+
+    pub fn date_format(&self) -> Option<&str> {
+        self.date_format.as_deref()
+    }
+
+    /// The epoch of serial numbers read into temporal columns, the 1900 date
+    /// system by default. Number cells use it; cells Excel formats as dates
+    /// are decoded with the date system the workbook itself declares.
+    pub fn with_date_system(mut self, date_system: DateSystem) -> Self {
+        self.date_system = date_system;
+        self
+    }
+
+    pub fn date_system(&self) -> DateSystem {
+        self.date_system
+    }
+
+    /// Infers the types of the columns the schema doesn't declare, all of
+    /// them when the schema is empty, from the first `rows` rows: csv files
+    /// as polars infers them, Excel cells as `Float64` when every value is a
+    /// number and `String` otherwise. Declared columns are matched to the
+    /// file's header by name. Each side is inferred on its own, so the same
+    /// column may get different types on the two sides.
+    pub fn with_infer_schema(mut self, rows: usize) -> Self {
+        self.infer_schema = Some(rows);
+        self
+    }
+
+    pub fn infer_schema(&self) -> Option<usize> {
+        self.infer_schema
+    }
+
+    /// Finds each schema column of an Excel source by its name in the
+    /// header row, so the columns may appear in any order. Columns are read
+    /// in the schema's order; schema columns missing from the header, unless
+    /// typed `Null`, and header columns missing from the schema are errors.
+    pub fn with_match_by_name(mut self, match_by_name: bool) -> Self {
+        self.match_by_name = match_by_name;
+        self
+    }
+
+    pub fn match_by_name(&self) -> bool {
+        self.match_by_name
+    }
+
+    /// Checks that the file's header names every schema column, in any
+    /// order, before any value is read. Sources without a header, such as
+    /// csv files without one, are not checked. See
+    /// [`ReadOptions::with_strict_schema`] to also check order and count.
+    pub fn with_check_header_names(mut self, check: bool) -> Self {
+        self.check_header_names = check;
+        self
+    }
+
+    pub fn check_header_names(&self) -> bool {
+        self.check_header_names
+    }
+
+    /// Reads at most `n_rows` data rows of each side, headers not counted.
+    /// Csv and typed formats push the limit into the scan. Excel readers stop
+    /// taking rows once they have them; streamed cells (see
+    /// [`ReadOptions::with_stream_cells`]) stop being read as well.
+    pub fn with_n_rows(mut self, n_rows: usize) -> Self {
+        self.n_rows = Some(n_rows);
+        self
+    }
+
+    pub fn n_rows(&self) -> Option<usize> {
+        self.n_rows
+    }
+
+    /// Reads tables, pivot tables and sheet ranges of `workbook`'s file from
+    /// the already opened `workbook` instead of opening the file again. Pass
+    /// a clone to the options of each kind read from the same workbook.
+    pub fn with_workbook(mut self, workbook: Workbook) -> Self {
+        self.workbook = Some(workbook);
+        self
+    }
+
+    pub fn workbook(&self) -> Option<&Workbook> {
+        self.workbook.as_ref()
+    }
+
+    /// Transcodes text files from `encoding` to UTF-8 before reading them,
+    /// for files in a legacy encoding such as `"windows-1252"` or
+    /// `"shift_jis"`. Takes the WHATWG encoding labels; a byte order mark in
+    /// the file takes precedence. The whole file is read into memory.
+    pub fn with_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    pub fn encoding(&self) -> Option<&str> {
+        self.encoding.as_deref()
+    }
+
+    /// Decodes the text of `binary` columns as `encoding` describes. Text
+    /// that doesn't decode is an invalid value.
+    pub fn with_binary_encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = encoding;
+        self
+    }
+
+    pub fn binary_encoding(&self) -> BinaryEncoding {
+        self.binary_encoding
+    }
+
+    /// Reads `token` as null in csv column `column` only, where other columns
+    /// may hold it as data. A column can take several tokens, on top of the
+    /// `Comparable`'s null values, which apply to every column. Tokens apply
+    /// to the columns the schema declares.
+    pub fn with_column_null_value(
+        mut self,
+        column: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        self.column_null_values.push((column.into(), token.into()));
+        self
+    }
+
+    pub fn column_null_values(&self) -> &[(String, String)] {
+        &self.column_null_values
+    }
+
+    /// Trims whitespace from the values of every string column, on both
+    /// sides alike, so trailing spaces don't show up as differences.
+    pub fn with_trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn trim(&self) -> Trim {
+        self.trim
+    }
+
+    /// Normalizes or removes the line breaks inside string values, on both
+    /// sides alike, so an Excel cell's `\r\n` matches a csv field's `\n`.
+    /// Only string columns are affected.
+    pub fn with_newlines(mut self, newlines: Newlines) -> Self {
+        self.newlines = newlines;
+        self
+    }
+
+    pub fn newlines(&self) -> Newlines {
+        self.newlines
+    }
+
+    /// Counts the rows of each side into the [`ReadReport`](crate::ReadReport),
+    /// to tell an empty read without collecting it. Excel reads have the rows
+    /// at hand; csv and typed formats take a counting pass over the file.
+    pub fn with_count_rows(mut self, count: bool) -> Self {
+        self.count_rows = count;
+        self
+    }
+
+    pub fn count_rows(&self) -> bool {
+        self.count_rows
+    }
+
+    /// Skips the first `rows` rows of a sheet range, such as a title above
+    /// the data, before its header row when it has one. Applies to sheet
+    /// ranges, defined names and multi-sheet reads; tables and pivot tables
+    /// know their own header. Csv files skip lines as their `Comparable`
+    /// says.
+    pub fn with_skip_rows(mut self, rows: usize) -> Self {
+        self.skip_rows = rows;
+        self
+    }
+
+    pub fn skip_rows(&self) -> usize {
+        self.skip_rows
+    }
+
+    /// Reads the range `reference` gives in A1 notation, such as `"A1:D100"`,
+    /// `"B2"` or `"Summary!B:D"`, instead of the `SheetRange`'s coordinates.
+    /// Full columns end with the sheet's used range. A sheet named in the
+    /// reference replaces the `SheetRange`'s sheet; multi-sheet reads use
+    /// only the bounds.
+    pub fn with_range(mut self, reference: impl Into<String>) -> Self {
+        self.range = Some(reference.into());
+        self
+    }
+
+    pub fn range(&self) -> Option<&str> {
+        self.range.as_deref()
+    }
+
+    /// Skips the first `offset` data rows of Parquet, Arrow IPC and ndjson
+    /// files, to read a slice of a large file together with
+    /// [`ReadOptions::with_n_rows`]. Both are pushed into the scan, which
+    /// skips the Parquet row groups outside the slice.
+    pub fn with_row_offset(mut self, offset: usize) -> Self {
+        self.row_offset = offset;
+        self
+    }
+
+    pub fn row_offset(&self) -> usize {
+        self.row_offset
+    }
+
+    /// Strips `%` signs from numeric columns before they are parsed, so
+    /// `12.5%` reads as `12.5`; the value isn't divided by 100.
+    pub fn with_strip_percent_signs(mut self, strip: bool) -> Self {
+        self.strip_percent_signs = strip;
+        self
+    }
+
+    pub fn strip_percent_signs(&self) -> bool {
+        self.strip_percent_signs
+    }
+
+    /// Reads sheet column `column`, such as `C`, as the targets of its
+    /// cells' hyperlinks rather than their display text, null for cells
+    /// without a link. Sheet range reads of .xlsx workbooks only.
+    pub fn with_hyperlink_column(mut self, column: impl Into<String>) -> Self {
+        self.hyperlink_column = Some(column.into());
+        self
+    }
+
+    pub fn hyperlink_column(&self) -> Option<&str> {
+        self.hyperlink_column.as_deref()
+    }
+
+    /// Reads the range of the sheet's autofilter, its header row and data,
+    /// in place of the sheet range bounds, checking the header against the
+    /// schema. The filter bounds the table more closely than the used range,
+    /// which takes in stray cells around it. Sheets without a filter fail to
+    /// read. Sheet range reads of .xlsx workbooks only.
+    pub fn with_auto_filter_range(mut self, auto_filter: bool) -> Self {
+        self.auto_filter_range = auto_filter;
+        self
+    }
+
+    pub fn auto_filter_range(&self) -> bool {
+        self.auto_filter_range
+    }
+
+    /// Leaves out the columns Excel hides in collapsed outline groups, so a
+    /// sheet reads as an analyst sees it with its groups as they were saved.
+    /// Off by default, which reads every column. Sheet range reads of .xlsx
+    /// workbooks only.
+    pub fn with_skip_collapsed_columns(mut self, skip: bool) -> Self {
+        self.skip_collapsed_columns = skip;
+        self
+    }
+
+    pub fn skip_collapsed_columns(&self) -> bool {
+        self.skip_collapsed_columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_checks_the_encoding_label() {
+        assert!(ReadOptions::new().validate().is_ok());
+        assert!(
+            ReadOptions::new()
+                .with_encoding("latin1")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ReadOptions::new()
+                .with_encoding("klingon")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ReadOptions::new()
+                .with_encoding("latin1")
+                .with_transpose(true)
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn parse_bool_reads_each_default_token() {
+        let options = ReadOptions::new();
+        for token in ["true", "yes", "y", "1"] {
+            assert_eq!(options.parse_bool(token), Some(true), "{token}");
+        }
+        for token in ["false", "no", "n", "0"] {
+            assert_eq!(options.parse_bool(token), Some(false), "{token}");
+        }
+        assert_eq!(options.parse_bool(" Yes "), Some(true));
+        assert_eq!(options.parse_bool("N"), Some(false));
+        assert_eq!(options.parse_bool("maybe"), None);
+    }
+
+    #[test]
+    fn parse_bool_uses_the_configured_tokens() {
+        let options = ReadOptions::new().with_bool_tokens(["on"], ["off"]);
+        assert_eq!(options.parse_bool("ON"), Some(true));
+        assert_eq!(options.parse_bool("off"), Some(false));
+        assert_eq!(options.parse_bool("yes"), None);
+    }
+
+    #[test]
+    fn parse_bool_reads_localized_defaults() {
+        let options = ReadOptions::new();
+        assert_eq!(options.parse_bool("WAHR"), Some(true));
+        assert_eq!(options.parse_bool("FALSCH"), Some(false));
+        assert_eq!(options.parse_bool("vrai"), Some(true));
+        assert_eq!(options.parse_bool("Faux"), Some(false));
+    }
+
+    #[test]
+    fn validate_checks_the_locale() {
+        assert!(ReadOptions::new().with_locale("de_DE").validate().is_ok());
+        assert!(ReadOptions::new().with_locale("xx_XX").validate().is_err());
+    }
+
+    #[test]
+    fn validate_checks_the_range() {
+        assert!(ReadOptions::new().with_range("A1:C3").validate().is_ok());
+        assert!(
+            ReadOptions::new()
+                .with_range("Summary!A1:D20")
+                .validate()
+                .is_ok()
+        );
+        assert!(ReadOptions::new().with_range("D1:A1").validate().is_err());
+    }
+}
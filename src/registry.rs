@@ -0,0 +1,193 @@
+use crate::error::ReadError;
+use crate::options::ReadOptions;
+use crate::report::ReadReport;
+use crate::{
+    PhantomDefinedNameReader, PhantomFixedWidthReader, PhantomMultiSheetReader,
+    PhantomPivotTableReader, PhantomSheetRangeReader, PhantomTableReader, PhantomTxtReader, Read,
+    Reader, Side, read_both,
+};
+use polars::prelude::LazyFrame;
+use qa_settings::Comparable;
+use qa_settings::qa_kind::QaKind;
+use std::fmt;
+use std::sync::Arc;
+
+/// Reads one side of a [`Comparable`] of the kinds it is registered for in a
+/// [`ReaderRegistry`]. Functions and closures with the signature of
+/// [`KindReader::read`] are readers.
+pub trait KindReader: Send + Sync {
+    fn read(
+        &self,
+        comp: &Comparable,
+        options: &ReadOptions,
+        side: Side,
+    ) -> Result<(LazyFrame, ReadReport), ReadError>;
+}
+
+impl<F> KindReader for F
+where
+    F: Fn(&Comparable, &ReadOptions, Side) -> Result<(LazyFrame, ReadReport), ReadError>
+        + Send
+        + Sync,
+{
+    fn read(
+        &self,
+        comp: &Comparable,
+        options: &ReadOptions,
+        side: Side,
+    ) -> Result<(LazyFrame, ReadReport), ReadError> {
+        self(comp, options, side)
+    }
+}
+
+/// Picks the reader for a `Comparable` by its `QaKind`. The default registry
+/// holds the text, pivot table, table and sheet range readers that
+/// [`get_lazy_frames`](crate::get_lazy_frames) uses; register more with
+/// [`ReaderRegistry::with_reader`] to read other kinds, or to replace a
+/// built-in reader.
+#[derive(Clone)]
+pub struct ReaderRegistry {
+    readers: Vec<(fn(&QaKind) -> bool, Arc<dyn KindReader>)>,
+}
+
+impl ReaderRegistry {
+    /// A registry without any readers.
+    pub fn empty() -> Self {
+        Self {
+            readers: Vec::new(),
+        }
+    }
+
+    /// Reads the kinds `matches` accepts with `reader`, ahead of the readers
+    /// registered before.
+    pub fn with_reader(
+        mut self,
+        matches: fn(&QaKind) -> bool,
+        reader: impl KindReader + 'static,
+    ) -> Self {
+        self.readers.push((matches, Arc::new(reader)));
+        self
+    }
+
+    fn reader(&self, kind: &QaKind) -> Result<&dyn KindReader, ReadError> {
+        self.readers
+            .iter()
+            .rev()
+            .find(|(matches, _)| matches(kind))
+            .map(|(_, reader)| reader.as_ref())
+            .ok_or_else(|| {
+                format!(
+                    "Reader for kind '{}' is not implemented",
+                    kind.as_str_kind()
+                )
+                .into()
+            })
+    }
+
+    /// Reads one side along with its [`ReadReport`].
+    pub fn get_lazy_frame_with_report(
+        &self,
+        comp: &Comparable,
+        options: &ReadOptions,
+        side: Side,
+    ) -> Result<(LazyFrame, ReadReport), ReadError> {
+        self.reader(comp.kind())?.read(comp, options, side)
+    }
+
+    /// Reads both sides concurrently, as
+    /// [`Reader::get_lazy_frames_with_report`] does.
+    pub fn get_lazy_frames_with_report(
+        &self,
+        comp: &Comparable,
+        options: &ReadOptions,
+    ) -> Result<((LazyFrame, ReadReport), (LazyFrame, ReadReport)), ReadError> {
+        let reader = self.reader(comp.kind())?;
+        read_both(|side| reader.read(comp, options, side))
+    }
+}
+
+impl Default for ReaderRegistry {
+    fn default() -> Self {
+        Self::empty()
+            .with_reader(
+                |kind| matches!(kind, QaKind::Txt | QaKind::Csv),
+                read_builtin,
+            )
+            .with_reader(|kind| matches!(kind, QaKind::PivotTable(_)), read_builtin)
+            .with_reader(|kind| matches!(kind, QaKind::Table(_)), read_builtin)
+            .with_reader(|kind| matches!(kind, QaKind::SheetRange(_)), read_builtin)
+    }
+}
+
+impl fmt::Debug for ReaderRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReaderRegistry")
+            .field("readers", &self.readers.len())
+            .finish()
+    }
+}
+
+/// Work done with the built-in reader of a kind, whose type depends on the
+/// kind and the options.
+pub(crate) trait WithReader {
+    type Output;
+    fn with<T>(self, reader: &Reader<'static, T>) -> Self::Output
+    where
+        for<'a> &'a Reader<'static, T>: Read;
+}
+
+/// Runs `work` with the built-in reader of `comp`: the text, fixed width,
+/// pivot table, table, defined name, multi-sheet or sheet range reader, as
+/// the kind and options select. `None` for kinds without one.
+pub(crate) fn with_builtin<W: WithReader>(
+    comp: &Comparable,
+    options: &ReadOptions,
+    work: W,
+) -> Option<W::Output> {
+    fn reader<T>(comp: &Comparable, options: &ReadOptions) -> Reader<'static, T> {
+        Reader::new(comp.clone()).with_options(options.clone())
+    }
+    Some(match comp.kind() {
+        QaKind::Txt | QaKind::Csv if options.fixed_width().is_some() => {
+            work.with(&reader::<PhantomFixedWidthReader>(comp, options))
+        }
+        QaKind::Txt | QaKind::Csv => work.with(&reader::<PhantomTxtReader>(comp, options)),
+        QaKind::PivotTable(_) => work.with(&reader::<PhantomPivotTableReader>(comp, options)),
+        QaKind::Table(_) => work.with(&reader::<PhantomTableReader>(comp, options)),
+        QaKind::SheetRange(_) if options.defined_name().is_some() => {
+            work.with(&reader::<PhantomDefinedNameReader>(comp, options))
+        }
+        QaKind::SheetRange(_) if !options.sheets().is_empty() => {
+            work.with(&reader::<PhantomMultiSheetReader>(comp, options))
+        }
+        QaKind::SheetRange(_) => work.with(&reader::<PhantomSheetRangeReader>(comp, options)),
+        _ => return None,
+    })
+}
+
+/// Reads one side with its built-in reader.
+struct ReadSide(Side);
+
+impl WithReader for ReadSide {
+    type Output = Result<(LazyFrame, ReadReport), ReadError>;
+    fn with<T>(self, reader: &Reader<'static, T>) -> Self::Output
+    where
+        for<'a> &'a Reader<'static, T>: Read,
+    {
+        reader.get_lazy_frame_with_report(self.0)
+    }
+}
+
+fn read_builtin(
+    comp: &Comparable,
+    options: &ReadOptions,
+    side: Side,
+) -> Result<(LazyFrame, ReadReport), ReadError> {
+    with_builtin(comp, options, ReadSide(side)).unwrap_or_else(|| {
+        Err(format!(
+            "Reader for kind '{}' is not implemented",
+            comp.kind().as_str_kind()
+        )
+        .into())
+    })
+}
@@ -0,0 +1,67 @@
+use crate::error::ReadError;
+use polars::prelude::PlSmallStr;
+use polars_core::prelude::AnyValue;
+use std::collections::BTreeMap;
+
+/// What a read did to the data beyond applying the schema, for one side of a
+/// comparison.
+#[derive(Debug, Clone, Default)]
+pub struct ReadReport {
+    /// Columns the schema marks as removed (`Null`, `x`, `remove`, ...), which
+    /// were excluded by configuration rather than absent from the source.
+    pub ignored_columns: Vec<PlSmallStr>,
+    /// Schema columns left out of the frame by
+    /// [`ReadOptions::with_include_columns`](crate::ReadOptions::with_include_columns),
+    /// which were not read.
+    pub unread_columns: Vec<PlSmallStr>,
+    /// Schema columns absent from the source header, removed columns aside.
+    /// Sources read by position misalign their columns when there are any.
+    /// Only filled when the header is checked, with
+    /// [`ReadOptions::with_strict_schema`](crate::ReadOptions::with_strict_schema)
+    /// or [`ReadOptions::with_check_header_names`](crate::ReadOptions::with_check_header_names).
+    pub missing_columns: Vec<PlSmallStr>,
+    /// Source header columns the schema doesn't declare, filled like
+    /// `missing_columns`.
+    pub extra_columns: Vec<PlSmallStr>,
+    /// Columns whose nulls were replaced, with the sentinel used, so the
+    /// replacement can be reversed.
+    pub null_sentinels: Vec<(PlSmallStr, AnyValue<'static>)>,
+    /// Columns backed by formulas, whose values are the ones cached at the
+    /// last Excel recalculation.
+    pub formula_columns: Vec<PlSmallStr>,
+    /// Rows dropped as duplicates, when deduplication is on.
+    pub duplicates_removed: Option<usize>,
+    /// String cells that held control characters, when they are stripped.
+    pub control_char_cells: Option<usize>,
+    /// String columns with values longer than the configured maximum.
+    pub long_string_columns: Vec<PlSmallStr>,
+    /// Values of the requested named cells, `None` where the cell is empty or
+    /// outside the sheet.
+    pub named_cells: BTreeMap<String, Option<calamine::Data>>,
+    /// Invalid UTF-8 sequences replaced with U+FFFD, when text files are read
+    /// without enforcing UTF-8. A count above zero suggests the file has
+    /// another encoding.
+    pub invalid_utf8: Option<usize>,
+    /// Columns of the returned frame, removed columns not counted.
+    pub columns: usize,
+    /// Rows of the returned frame, when counting them is on.
+    pub rows: Option<usize>,
+    /// The slice of a typed file read, as the rows skipped and the most rows
+    /// taken, when either is set.
+    pub row_slice: Option<(usize, Option<usize>)>,
+}
+
+/// What [`validate`](crate::validate) found would keep each side of a
+/// comparison from being read.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub left: Vec<ReadError>,
+    pub right: Vec<ReadError>,
+}
+
+impl ValidationReport {
+    /// Whether neither side has problems.
+    pub fn is_valid(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+}
@@ -0,0 +1,290 @@
+use crate::error::ReadError;
+use polars::prelude::{PlSmallStr, Schema};
+use polars_core::prelude::{DataType, UnknownKind};
+
+/// Types the columns of a file header from a dictionary of known columns,
+/// ignoring dictionary entries absent from the file. Header columns missing
+/// from the dictionary are an error naming all of them.
+pub fn from_dictionary<S: AsRef<str>>(
+    dictionary: &Schema,
+    header: &[S],
+) -> Result<Schema, ReadError> {
+    let mut schema = Schema::with_capacity(header.len());
+    let mut unmapped = Vec::new();
+    for name in header {
+        match dictionary.get(name.as_ref()) {
+            Some(dtype) => {
+                schema.insert(name.as_ref().into(), dtype.clone());
+            }
+            None => unmapped.push(name.as_ref()),
+        }
+    }
+    if !unmapped.is_empty() {
+        return Err(format!(
+            "No type in the schema dictionary for columns: {}",
+            unmapped.join(", ")
+        )
+        .into());
+    }
+    Ok(schema)
+}
+
+/// Checks that a file header holds exactly the schema columns, in order.
+/// The error lists missing and unexpected columns, or the first position
+/// where the order differs.
+pub fn check_strict<S: AsRef<str>>(expected: &Schema, header: &[S]) -> Result<(), ReadError> {
+    let found = header.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+    let missing = expected
+        .iter_names()
+        .filter(|name| !found.contains(&name.as_str()))
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>();
+    let unexpected = found
+        .iter()
+        .filter(|name| expected.get(name).is_none())
+        .copied()
+        .collect::<Vec<_>>();
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing columns: {}", missing.join(", ")));
+    }
+    if !unexpected.is_empty() {
+        problems.push(format!("unexpected columns: {}", unexpected.join(", ")));
+    }
+    if problems.is_empty() && found.len() != expected.len() {
+        problems.push(format!(
+            "{} columns found but the schema declares {}",
+            found.len(),
+            expected.len()
+        ));
+    }
+    if problems.is_empty()
+        && let Some((position, (name, header))) = expected
+            .iter_names()
+            .zip(found.iter())
+            .enumerate()
+            .find(|(_, (name, header))| name.as_str() != **header)
+    {
+        problems.push(format!(
+            "column {} is '{header}' but the schema expects '{name}'",
+            position + 1
+        ));
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "File columns differ from the schema: {}",
+        problems.join("; ")
+    )
+    .into())
+}
+
+/// The schema columns missing from a file header, removed columns aside,
+/// and the header columns the schema doesn't declare, each in order.
+pub fn unmatched<S: AsRef<str>>(
+    schema: &Schema,
+    header: &[S],
+) -> (Vec<PlSmallStr>, Vec<PlSmallStr>) {
+    let missing = schema
+        .iter()
+        .filter(|(name, dtype)| {
+            *dtype != &DataType::Null && !header.iter().any(|h| h.as_ref().trim() == name.as_str())
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    let extra = header
+        .iter()
+        .map(|h| h.as_ref().trim())
+        .filter(|h| schema.get(h).is_none())
+        .map(PlSmallStr::from)
+        .collect();
+    (missing, extra)
+}
+
+/// Types the columns of a file header from a schema declaring some of them,
+/// leaving the types of the others to inference. Header names are trimmed.
+/// Declared columns missing from the header are an error naming all of them,
+/// unless typed `Null`.
+pub fn partial<S: AsRef<str>>(declared: &Schema, header: &[S]) -> Result<Schema, ReadError> {
+    let missing = declared
+        .iter()
+        .filter(|(name, dtype)| {
+            *dtype != &DataType::Null && !header.iter().any(|h| h.as_ref().trim() == name.as_str())
+        })
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Schema columns missing from the file: {}",
+            missing.join(", ")
+        )
+        .into());
+    }
+    Ok(header
+        .iter()
+        .map(|name| {
+            let name = name.as_ref().trim();
+            let dtype = declared
+                .get(name)
+                .cloned()
+                .unwrap_or(DataType::Unknown(UnknownKind::Any));
+            (name.into(), dtype)
+        })
+        .collect())
+}
+
+/// The position in `header` of each schema column, `None` for removed
+/// columns the header lacks. Other schema columns missing from the header,
+/// and header columns missing from the schema, are an error naming all of
+/// them.
+pub fn positions<S: AsRef<str>>(
+    schema: &Schema,
+    header: &[S],
+) -> Result<Vec<Option<usize>>, ReadError> {
+    let positions = schema
+        .iter_names()
+        .map(|name| {
+            header
+                .iter()
+                .position(|h| h.as_ref().trim() == name.as_str())
+        })
+        .collect::<Vec<_>>();
+    let missing = schema
+        .iter()
+        .zip(&positions)
+        .filter(|((_, dtype), position)| position.is_none() && *dtype != &DataType::Null)
+        .map(|((name, _), _)| name.as_str())
+        .collect::<Vec<_>>();
+    let extra = header
+        .iter()
+        .map(|h| h.as_ref().trim())
+        .filter(|h| schema.get(h).is_none())
+        .collect::<Vec<_>>();
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing columns: {}", missing.join(", ")));
+    }
+    if !extra.is_empty() {
+        problems.push(format!("columns not in the schema: {}", extra.join(", ")));
+    }
+    if !problems.is_empty() {
+        return Err(format!("Header differs from the schema: {}", problems.join("; ")).into());
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(columns: &[(&str, DataType)]) -> Schema {
+        columns
+            .iter()
+            .map(|(name, dtype)| (PlSmallStr::from(*name), dtype.clone()))
+            .collect()
+    }
+
+    fn columns(schema: &Schema) -> Vec<(&str, DataType)> {
+        schema
+            .iter()
+            .map(|(name, dtype)| (name.as_str(), dtype.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn partial_trims_header_names() {
+        let declared = schema(&[("a", DataType::Int64), ("b", DataType::String)]);
+        let typed = partial(&declared, &[" a", "b ", " c "]).unwrap();
+        assert_eq!(
+            columns(&typed),
+            [
+                ("a", DataType::Int64),
+                ("b", DataType::String),
+                ("c", DataType::Unknown(UnknownKind::Any))
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_types_declared_columns_and_infers_the_rest() {
+        let declared = schema(&[("a", DataType::Int64), ("gone", DataType::Null)]);
+        let typed = partial(&declared, &["a", "b"]).unwrap();
+        assert_eq!(
+            columns(&typed),
+            [
+                ("a", DataType::Int64),
+                ("b", DataType::Unknown(UnknownKind::Any))
+            ]
+        );
+        let declared = schema(&[("a", DataType::Int64), ("c", DataType::String)]);
+        let error = partial(&declared, &["a"]).unwrap_err().to_string();
+        assert!(error.contains("Schema columns missing from the file: c"));
+    }
+
+    #[test]
+    fn from_dictionary_types_the_header_columns() {
+        let dictionary = schema(&[
+            ("a", DataType::Int64),
+            ("b", DataType::String),
+            ("c", DataType::Float64),
+        ]);
+        let typed = from_dictionary(&dictionary, &["b", "a"]).unwrap();
+        assert_eq!(
+            columns(&typed),
+            [("b", DataType::String), ("a", DataType::Int64)]
+        );
+        let error = from_dictionary(&dictionary, &["a", "z"]).unwrap_err();
+        assert!(error.to_string().contains("z"));
+    }
+
+    #[test]
+    fn check_strict_accepts_only_the_exact_columns() {
+        let expected = schema(&[("a", DataType::Int64), ("b", DataType::String)]);
+        assert!(check_strict(&expected, &["a", "b"]).is_ok());
+        let error = check_strict(&expected, &["a", "c"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("missing columns: b"));
+        assert!(error.contains("unexpected columns: c"));
+        let error = check_strict(&expected, &["b", "a"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("column 1 is 'b' but the schema expects 'a'"));
+        let error = check_strict(&expected, &["a", "b", "b"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("3 columns found but the schema declares 2"));
+    }
+
+    #[test]
+    fn positions_finds_columns_by_name() {
+        let declared = schema(&[
+            ("b", DataType::Int64),
+            ("a", DataType::String),
+            ("gone", DataType::Null),
+        ]);
+        assert_eq!(
+            positions(&declared, &[" a ", "b"]).unwrap(),
+            [Some(1), Some(0), None]
+        );
+        let error = positions(&declared, &["a", "b", "x"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("columns not in the schema: x"));
+        let error = positions(&declared, &["a"]).unwrap_err().to_string();
+        assert!(error.contains("missing columns: b"));
+    }
+
+    #[test]
+    fn unmatched_lists_missing_and_extra_columns() {
+        let declared = schema(&[
+            ("a", DataType::Int64),
+            ("b", DataType::String),
+            ("gone", DataType::Null),
+        ]);
+        let (missing, extra) = unmatched(&declared, &["a ", "x"]);
+        assert_eq!(missing, [PlSmallStr::from("b")]);
+        assert_eq!(extra, [PlSmallStr::from("x")]);
+    }
+}
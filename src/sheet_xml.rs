@@ -0,0 +1,350 @@
+use crate::a1;
+use crate::error::ReadError;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read as _;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// The XML of one worksheet of an xlsx workbook, for the parts of a sheet
+/// calamine doesn't read, with the relationships of the sheet's part.
+pub(crate) struct SheetXml {
+    xml: String,
+    /// Relationship ids, such as those of hyperlinks, to their targets.
+    rels: HashMap<String, String>,
+}
+
+impl SheetXml {
+    /// Reads the XML of `sheet` from xlsx workbook `file`, finding its part
+    /// through the workbook's relationships.
+    pub(crate) fn open(file: &Path, sheet: &str) -> Result<Self, ReadError> {
+        let mut archive = zip::ZipArchive::new(File::open(file)?)?;
+        let workbook = member(&mut archive, "xl/workbook.xml")?;
+        let id = elements(&workbook, "sheet")
+            .find(|tag| attribute(tag, "name").as_deref() == Some(sheet))
+            .and_then(|tag| attribute(tag, "id"))
+            .ok_or_else(|| ReadError::NotFound {
+                what: "sheet",
+                name: sheet.to_string(),
+            })?;
+        let targets = relationships(&member(&mut archive, "xl/_rels/workbook.xml.rels")?);
+        let target = targets
+            .get(id.as_ref())
+            .ok_or_else(|| format!("The workbook has no part for sheet '{sheet}'"))?;
+        let path = part_path("xl", target);
+        let xml = member(&mut archive, &path)?;
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", &path));
+        let rels = match member(&mut archive, &format!("{dir}/_rels/{name}.rels")) {
+            Ok(rels) => relationships(&rels),
+            Err(ReadError::Zip(zip::result::ZipError::FileNotFound)) => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { xml, rels })
+    }
+
+    /// The hyperlink targets of the cells of zero-based `column` in `rows`,
+    /// by row: the address of an external link, with the place within it
+    /// after a `#`, or the place in the workbook, such as `Sheet2!A1`, an
+    /// internal link goes to.
+    pub(crate) fn hyperlinks(
+        &self,
+        column: u32,
+        rows: RangeInclusive<u32>,
+    ) -> Result<HashMap<u32, String>, ReadError> {
+        let mut links = HashMap::new();
+        for tag in elements(&self.xml, "hyperlink") {
+            let Some(reference) = attribute(tag, "ref") else {
+                continue;
+            };
+            let (_, start, end) = a1::range_reference(&reference)?;
+            if column < start.1 || column > end.1 {
+                continue;
+            }
+            let address = attribute(tag, "id").and_then(|id| self.rels.get(id.as_ref()));
+            let target = match (address, attribute(tag, "location")) {
+                (Some(address), Some(location)) => format!("{address}#{location}"),
+                (Some(address), None) => address.clone(),
+                (None, Some(location)) => location.into_owned(),
+                (None, None) => continue,
+            };
+            for row in start.0.max(*rows.start())..=end.0.min(*rows.end()) {
+                // the first link of a cell is the one Excel follows
+                links.entry(row).or_insert_with(|| target.clone());
+            }
+        }
+        Ok(links)
+    }
+
+    /// The zero-based columns hidden in collapsed outline groups, in order.
+    /// Columns hidden outside any group aren't among them.
+    pub(crate) fn collapsed_columns(&self) -> Vec<u32> {
+        let is_set = |tag: &str, name: &str| {
+            attribute(tag, name).is_some_and(|value| value == "1" || value == "true")
+        };
+        let level =
+            |tag: &str| attribute(tag, "outlineLevel").and_then(|level| level.parse::<u8>().ok());
+        elements(&self.xml, "col")
+            .filter(|tag| is_set(tag, "hidden") && level(tag).is_some_and(|level| level > 0))
+            .filter_map(|tag| {
+                let min = attribute(tag, "min")?.parse::<u32>().ok()?;
+                let max = attribute(tag, "max")?.parse::<u32>().ok()?;
+                Some(min.checked_sub(1)?..max)
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The zero-based start and end of the sheet's autofilter, `None` when
+    /// no filter is applied.
+    pub(crate) fn auto_filter(&self) -> Result<Option<((u32, u32), (u32, u32))>, ReadError> {
+        // the sheet's own filter comes before those of its custom views
+        let Some(reference) =
+            elements(&self.xml, "autoFilter").find_map(|tag| attribute(tag, "ref"))
+        else {
+            return Ok(None);
+        };
+        let (_, start, end) = a1::range_reference(&reference)?;
+        Ok(Some((start, end)))
+    }
+}
+
+/// Reads the part `name` of an xlsx archive as text.
+fn member(archive: &mut zip::ZipArchive<File>, name: &str) -> Result<String, ReadError> {
+    let mut xml = String::new();
+    archive.by_name(name)?.read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+/// The path in the archive of a relationship `target` of a part in `dir`.
+fn part_path(dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments = dir.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// The targets of the relationships of a `.rels` part, by id.
+fn relationships(xml: &str) -> HashMap<String, String> {
+    elements(xml, "Relationship")
+        .filter_map(|tag| Some((attribute(tag, "Id")?, attribute(tag, "Target")?)))
+        .map(|(id, target)| (id.into_owned(), target.into_owned()))
+        .collect()
+}
+
+/// The attribute text of each start tag of elements named `name`, with or
+/// without a namespace prefix, in document order.
+fn elements<'x>(xml: &'x str, name: &'x str) -> impl Iterator<Item = &'x str> {
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        loop {
+            let open = rest.find('<')?;
+            let tag = &rest[open + 1..];
+            let end = tag_end(tag)?;
+            rest = &tag[end..];
+            let tag = tag[..end].trim_end_matches('/');
+            let split = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+            let (tag_name, attributes) = tag.split_at(split);
+            // end tags, declarations and comments have no attributes to read
+            if !tag_name.starts_with(['/', '?', '!']) && local_name(tag_name) == name {
+                return Some(attributes);
+            }
+        }
+    })
+}
+
+/// The offset of the `>` closing a tag, skipping quoted attribute values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The value of the attribute named `name`, with or without a namespace
+/// prefix, in the attribute text of a start tag, unescaped.
+fn attribute<'x>(attributes: &'x str, name: &str) -> Option<Cow<'x, str>> {
+    let mut rest = attributes;
+    loop {
+        let (key, after) = rest.split_once('=')?;
+        let after = after.trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let (value, next) = after[1..].split_once(quote)?;
+        if local_name(key.trim()) == name {
+            return Some(unescape(value));
+        }
+        rest = next;
+    }
+}
+
+/// The name of a tag or attribute without its namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+/// Replaces the predefined entities and character references of XML text.
+fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        unescaped.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semicolon) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semicolon];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[semicolon + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    Cow::Owned(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_are_found_by_local_name_and_unescaped() {
+        let tags = elements(
+            r#"<x:sheets><x:sheet name="Q&amp;A &#x263A;" r:id='rId2' sheetId="1"/><sheet id="rId9"/></x:sheets>"#,
+            "sheet",
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(attribute(tags[0], "name").unwrap(), "Q&A \u{263a}");
+        assert_eq!(attribute(tags[0], "id").unwrap(), "rId2");
+        assert_eq!(attribute(tags[1], "id").unwrap(), "rId9");
+        assert_eq!(attribute(tags[1], "name"), None);
+    }
+
+    #[test]
+    fn tags_end_outside_quoted_values() {
+        let tags = elements(
+            r#"<hyperlink ref="A1" display="a > b"/><hyperlink ref="A2">"#,
+            "hyperlink",
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(attribute(tags[0], "display").unwrap(), "a > b");
+        assert_eq!(attribute(tags[1], "ref").unwrap(), "A2");
+    }
+
+    #[test]
+    fn part_paths_resolve_against_their_directory() {
+        assert_eq!(
+            part_path("xl", "worksheets/sheet1.xml"),
+            "xl/worksheets/sheet1.xml"
+        );
+        assert_eq!(
+            part_path("xl", "/xl/worksheets/sheet2.xml"),
+            "xl/worksheets/sheet2.xml"
+        );
+        assert_eq!(
+            part_path("xl/worksheets", "../media/a.png"),
+            "xl/media/a.png"
+        );
+    }
+
+    #[test]
+    fn hyperlinks_read_external_and_internal_targets() {
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/a?x=1&amp;y=2" TargetMode="External"/></Relationships>"#;
+        let bytes = crate::tests::xlsx_bytes(
+            r#"<sheetData/><hyperlinks><hyperlink ref="B2" r:id="rId1"/><hyperlink ref="B3" location="Sheet2!A1"/><hyperlink ref="B5:C6" r:id="rId1" location="top"/><hyperlink ref="A2" location="Other!A1"/></hyperlinks>"#,
+            &[("xl/worksheets/_rels/sheet1.xml.rels", rels)],
+        );
+        let path = crate::tests::temp_file("hyperlinks.xlsx", &bytes);
+        let xml = SheetXml::open(&path, "Sheet1").unwrap();
+        let links = xml.hyperlinks(1, 0..=5).unwrap();
+        assert_eq!(links.len(), 4);
+        assert_eq!(links[&1], "https://example.com/a?x=1&y=2");
+        assert_eq!(links[&2], "Sheet2!A1");
+        assert_eq!(links[&4], "https://example.com/a?x=1&y=2#top");
+        assert_eq!(links[&5], links[&4]);
+        // rows outside the ones asked for are left out
+        assert!(xml.hyperlinks(1, 0..=3).unwrap().get(&4).is_none());
+        assert!(matches!(
+            SheetXml::open(&path, "Missing"),
+            Err(ReadError::NotFound { what: "sheet", .. })
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn auto_filter_is_the_sheets_own() {
+        let bytes = crate::tests::xlsx_bytes(
+            r#"<sheetData/><autoFilter ref="B3:D10"/><customSheetViews><customSheetView guid="{0}"><autoFilter ref="A1:A2"/></customSheetView></customSheetViews>"#,
+            &[],
+        );
+        let path = crate::tests::temp_file("filtered.xlsx", &bytes);
+        let xml = SheetXml::open(&path, "Sheet1").unwrap();
+        assert_eq!(xml.auto_filter().unwrap(), Some(((2, 1), (9, 3))));
+        std::fs::remove_file(&path).unwrap();
+        let bytes = crate::tests::xlsx_bytes("<sheetData/>", &[]);
+        let path = crate::tests::temp_file("unfiltered.xlsx", &bytes);
+        assert_eq!(
+            SheetXml::open(&path, "Sheet1")
+                .unwrap()
+                .auto_filter()
+                .unwrap(),
+            None
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn collapsed_columns_are_the_hidden_grouped_ones() {
+        let bytes = crate::tests::xlsx_bytes(
+            r#"<cols><col min="2" max="3" width="9" hidden="1" outlineLevel="1"/><col min="4" max="4" collapsed="1"/><col min="5" max="5" hidden="1"/><col min="6" max="6" outlineLevel="1"/></cols><sheetData/>"#,
+            &[],
+        );
+        let path = crate::tests::temp_file("outlined.xlsx", &bytes);
+        let xml = SheetXml::open(&path, "Sheet1").unwrap();
+        // B:C are collapsed, E is hidden outside a group and F is an expanded group
+        assert_eq!(xml.collapsed_columns(), [1, 2]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
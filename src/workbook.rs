@@ -0,0 +1,85 @@
+use crate::error::ReadError;
+use calamine::{Reader as _, Xlsx};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// An xlsx workbook opened once and shared by the reads of its tables,
+/// pivot tables and sheet ranges, which otherwise open and parse the file
+/// each. Hand it to the reads with
+/// [`ReadOptions::with_workbook`](crate::ReadOptions::with_workbook); reads
+/// of any other file open that file as usual.
+///
+/// Clones share the opened workbook. Reads using it take turns.
+#[derive(Clone)]
+pub struct Workbook {
+    path: PathBuf,
+    inner: Arc<Mutex<Opened>>,
+}
+
+/// An opened xlsx workbook, with its tables loaded on first use.
+pub(crate) struct Opened {
+    xlsx: Xlsx<BufReader<File>>,
+    tables_loaded: bool,
+}
+
+impl Opened {
+    pub(crate) fn new(xlsx: Xlsx<BufReader<File>>) -> Self {
+        Self {
+            xlsx,
+            tables_loaded: false,
+        }
+    }
+
+    pub(crate) fn xlsx(&mut self) -> &mut Xlsx<BufReader<File>> {
+        &mut self.xlsx
+    }
+
+    /// The workbook with its tables loaded, which only the first call does.
+    pub(crate) fn with_tables(&mut self) -> Result<&mut Xlsx<BufReader<File>>, ReadError> {
+        if !self.tables_loaded {
+            self.xlsx.load_tables()?;
+            self.tables_loaded = true;
+        }
+        Ok(&mut self.xlsx)
+    }
+}
+
+impl Workbook {
+    /// Opens the xlsx workbook at `path`. Reads use it for files given by
+    /// the same path.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ReadError> {
+        let path = path.into();
+        let xlsx = calamine::open_workbook(&path)?;
+        Ok(Self {
+            path,
+            inner: Arc::new(Mutex::new(Opened::new(xlsx))),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Runs `f` on the opened workbook, waiting for other reads using it.
+    pub(crate) fn with<R>(
+        &self,
+        f: impl FnOnce(&mut Opened) -> Result<R, ReadError>,
+    ) -> Result<R, ReadError> {
+        let mut opened = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut opened)
+    }
+}
+
+impl fmt::Debug for Workbook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Workbook")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}